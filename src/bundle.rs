@@ -0,0 +1,308 @@
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use gettextrs::gettext;
+use iced::Color;
+use image::{ImageOutputFormat, Rgb, RgbImage};
+use iced_style::theme::Palette;
+use oceania_style::{get_home, ButtonStyle, ListStyle, MenuStyle, SelectedTheme, ThemeCustom, ThemeSet};
+use serde_derive::{Serialize, Deserialize};
+use toml::{Table, Value};
+use zip::{CompressionMethod, ZipArchive};
+use crate::color::{hex, mix};
+use crate::export::ExportTarget;
+use crate::library::{self, Library};
+use crate::palette::{ThemePalette, Variant};
+use crate::swatch::zip_in_memory;
+use crate::theme::{self, Theme};
+
+/// newest bundle layout this build writes and understands
+pub const BUNDLE_VERSION: u32 = 1;
+pub const EXTENSION: &str = "tetra";
+
+/// a shareable `.tetra` archive. only `manifest.toml` and `theme.toml` are read back; the variants,
+/// `ThemeSet`, configs and preview are there for people browsing the bundle.
+///
+/// ```text
+/// manifest.toml
+/// theme.toml
+/// themeset.toml
+/// variants/{light,dark}.toml
+/// exports/<target>/<path below home>
+/// preview.png
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub name: String,
+    pub theme: Theme,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    bundle_version: u32,
+    name: String,
+    generator: String,
+}
+
+/// what to do when a bundle's name is already taken in the library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    Fail,
+    KeepBoth,
+    Replace,
+}
+
+/// `~/Oceania/exports/bundles/<name>.tetra`
+pub fn default_path(name: &str) -> PathBuf {
+    PathBuf::from(get_home()).join("Oceania/exports/bundles").join(format!("{name}.{EXTENSION}"))
+}
+
+fn rgb(color: Color) -> Rgb<u8> {
+    let rgba = color.into_rgba8();
+    Rgb([rgba[0], rgba[1], rgba[2]])
+}
+
+/// three background panes with a few lines of "text" over a strip of every slot
+pub fn preview(palette: &ThemePalette) -> RgbImage {
+    const SWATCH: u32 = 40;
+    let width = SWATCH * 11;
+    let panes = [palette.bg1, palette.bg2, palette.bg3];
+    let pane_width = width / 3 + 1;
+    let mut image = RgbImage::new(width, 180);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let pane = (x / pane_width) as usize;
+        *pixel = if y >= 140 {
+            rgb(palette.get(crate::palette::ColorSlot::ALL[(x / SWATCH) as usize]))
+        } else if (20..28).contains(&y) || (40..48).contains(&y) || (60..68).contains(&y) {
+            let line = (y - 20) / 20;
+            let inset = x % pane_width;
+            let length = pane_width - 24 - line * 30 - pane as u32 * 10;
+            if (12..12 + length).contains(&inset) {
+                let accent = [palette.txt, palette.blue, palette.green][line as usize];
+                rgb(if line == 0 { accent } else { mix(accent, panes[pane], 0.2) })
+            } else {
+                rgb(panes[pane])
+            }
+        } else {
+            rgb(panes[pane])
+        };
+    }
+    image
+}
+
+fn png(image: &RgbImage) -> Vec<u8> {
+    let mut out = Cursor::new(vec![]);
+    // encoding into memory can't fail short of a bug in the encoder
+    image.write_to(&mut out, ImageOutputFormat::Png).unwrap();
+    out.into_inner()
+}
+
+/// the theme and, when it was saved under the same name, its opposite variant. a saved variant
+/// belonging to some other theme stays out of the bundle.
+fn variants(theme: &Theme) -> Vec<(Variant, Theme)> {
+    let variant = theme.meta.variant_of(&theme.palette);
+    let mut out = vec![(variant, Theme { extends: None, ..theme.clone() })];
+    let other = theme::load(&variant.other().path(), &Library::home()).ok()
+        .filter(|other| !theme.meta.name.is_empty() && other.meta.name == theme.meta.name);
+    if let Some(other) = other {
+        out.push((variant.other(), Theme { extends: None, ..other }));
+    }
+    out
+}
+
+/// the widget styles of an Oceania app, laid out like Oceania's own light and dark themes
+pub fn theme_custom(palette: &ThemePalette) -> ThemeCustom {
+    let button = |bg_color| ButtonStyle {
+        border_radius: 2.0,
+        txt_color: palette.txt,
+        bg_color,
+        border_color: Color::from_rgb8(0, 0, 0),
+        border_width: 0.0,
+        shadow_offset: iced::Vector { x: 0.0, y: 0.0 }
+    };
+    ThemeCustom {
+        application: Palette {
+            background: palette.bg1,
+            text: palette.txt,
+            primary: palette.blue,
+            success: palette.green,
+            danger: palette.red,
+        },
+        sidebar: button(palette.bg2),
+        secondary: button(palette.bg3),
+        list: ListStyle {
+            txt_color: palette.txt,
+            bg_color: palette.bg1,
+            handle_color: palette.txt,
+            border_radius: 5.0,
+            border_width: 2.0,
+            border_color: palette.txt,
+            menu: MenuStyle {
+                txt_color: palette.txt,
+                bg_color: palette.bg1,
+                border_radius: 5.0,
+                border_width: 2.0,
+                border_color: palette.txt,
+                sel_txt_color: palette.bg1,
+                sel_bg_color: palette.blue,
+            }
+        }
+    }
+}
+
+/// light and dark from the bundled variants, falling back to Oceania's own, and the theme itself as custom
+fn theme_set(theme: &Theme, variants: &[(Variant, Theme)]) -> ThemeSet {
+    let style = |wanted: Variant, fallback: SelectedTheme| variants.iter()
+        .find(|(variant, _)| *variant == wanted)
+        .map(|(_, theme)| theme_custom(&theme.palette))
+        .unwrap_or_else(|| crate::generate_theme(fallback).unwrap());
+    ThemeSet {
+        light: style(Variant::Light, SelectedTheme::Light),
+        dark: style(Variant::Dark, SelectedTheme::Dark),
+        custom: theme_custom(&theme.palette),
+    }
+}
+
+/// a `ThemeSet` as toml, with oceania_style's field names and `#rrggbb` colors
+fn theme_set_toml(set: &ThemeSet) -> String {
+    fn table<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Table(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+    let color = |color: Color| Value::String(hex(&color));
+    let size = |size: f32| Value::Float(size.into());
+    let button = |style: &ButtonStyle| table([
+        ("border_radius", size(style.border_radius)),
+        ("txt_color", color(style.txt_color)),
+        ("bg_color", color(style.bg_color)),
+        ("border_color", color(style.border_color)),
+        ("border_width", size(style.border_width)),
+        ("shadow_offset", Value::Array(vec![size(style.shadow_offset.x), size(style.shadow_offset.y)])),
+    ]);
+    let custom = |style: &ThemeCustom| {
+        let (list, menu) = (&style.list, &style.list.menu);
+        table([
+            ("application", table([
+                ("background", color(style.application.background)),
+                ("text", color(style.application.text)),
+                ("primary", color(style.application.primary)),
+                ("success", color(style.application.success)),
+                ("danger", color(style.application.danger)),
+            ])),
+            ("sidebar", button(&style.sidebar)),
+            ("secondary", button(&style.secondary)),
+            ("list", table([
+                ("txt_color", color(list.txt_color)),
+                ("bg_color", color(list.bg_color)),
+                ("handle_color", color(list.handle_color)),
+                ("border_radius", size(list.border_radius)),
+                ("border_width", size(list.border_width)),
+                ("border_color", color(list.border_color)),
+                ("menu", table([
+                    ("txt_color", color(menu.txt_color)),
+                    ("bg_color", color(menu.bg_color)),
+                    ("border_radius", size(menu.border_radius)),
+                    ("border_width", size(menu.border_width)),
+                    ("border_color", color(menu.border_color)),
+                    ("sel_txt_color", color(menu.sel_txt_color)),
+                    ("sel_bg_color", color(menu.sel_bg_color)),
+                ])),
+            ])),
+        ])
+    };
+    let set: Table = [("light", &set.light), ("dark", &set.dark), ("custom", &set.custom)].into_iter()
+        .map(|(key, style)| (key.to_string(), custom(style)))
+        .collect();
+    toml::to_string(&set).unwrap()
+}
+
+pub fn encode(name: &str, theme: &Theme) -> Vec<u8> {
+    let manifest = Manifest { bundle_version: BUNDLE_VERSION, name: name.to_string(), generator: format!("Tetra {}", env!("CARGO_PKG_VERSION")) };
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("manifest.toml".to_string(), toml::to_string(&manifest).unwrap().into_bytes()),
+        ("theme.toml".to_string(), theme::to_string(&Theme { extends: None, ..theme.clone() }).into_bytes()),
+    ];
+    let variants = variants(theme);
+    files.push(("themeset.toml".to_string(), theme_set_toml(&theme_set(theme, &variants)).into_bytes()));
+    for (variant, variant_theme) in &variants {
+        files.push((format!("variants/{}.toml", variant.key()), theme::to_string(variant_theme).into_bytes()));
+    }
+    let home = PathBuf::from(get_home());
+    for target in ExportTarget::ALL {
+        for file in target.render(&theme.palette, &theme.meta) {
+            let relative = file.path.strip_prefix(&home).map(Path::to_path_buf).unwrap_or_else(|_| PathBuf::from(file.path.file_name().unwrap_or_default()));
+            files.push((format!("exports/{}/{}", target.key(), relative.display()), file.contents.into_bytes()));
+        }
+    }
+    files.push(("preview.png".to_string(), png(&preview(&theme.palette))));
+
+    zip_in_memory(files.iter().map(|(path, contents)| (path.as_str(), CompressionMethod::Deflated, contents.as_slice())))
+}
+
+/// refuses names `decode` wouldn't accept back, before anything is written
+pub fn export(name: &str, theme: &Theme, path: &Path) -> Result<(), String> {
+    library::validate(name)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    std::fs::write(path, encode(name, theme)).map_err(|error| error.to_string())
+}
+
+fn entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Result<String, String> {
+    let mut file = archive.by_name(name).map_err(|_| format!("{} {name}", gettext("The bundle is missing")))?;
+    let mut out = String::new();
+    file.read_to_string(&mut out).map_err(|error| format!("{name}: {error}"))?;
+    Ok(out)
+}
+
+pub fn decode(bytes: Vec<u8>) -> Result<Bundle, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|_| gettext("Not a Tetra bundle"))?;
+    let manifest: Manifest = toml::from_str(&entry(&mut archive, "manifest.toml")?).map_err(|error| format!("manifest.toml: {error}"))?;
+    if manifest.bundle_version > BUNDLE_VERSION {
+        return Err(gettext("The bundle was made by a newer version of Tetra"));
+    }
+    library::validate(&manifest.name)?;
+    let document = theme::parse(&entry(&mut archive, "theme.toml")?, &Library::home()).map_err(|error| format!("theme.toml: {error}"))?;
+    if document.extends.is_some() {
+        return Err(gettext("The bundled theme depends on a theme that isn't included"));
+    }
+    let theme = Theme { palette: ThemePalette::from_file(&document.colors), meta: document.meta, extends: None };
+    Ok(Bundle { name: manifest.name, theme })
+}
+
+pub fn read(path: &Path) -> Result<Bundle, String> {
+    decode(std::fs::read(path).map_err(|error| format!("{}: {error}", path.display()))?)
+}
+
+/// adds the bundle to the library, returning the name it was stored under
+pub fn install(bundle: &Bundle, conflict: Conflict) -> Result<String, String> {
+    let library = Library::home();
+    let name = match (library.exists(&bundle.name), conflict) {
+        (false, _) | (true, Conflict::Replace) => bundle.name.clone(),
+        (true, Conflict::KeepBoth) => library.unique_name(&bundle.name),
+        (true, Conflict::Fail) => return Err(format!("{} {}", gettext("A theme with that name already exists:"), bundle.name)),
+    };
+    library.save(&name, &bundle.theme)?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_carry_a_theme_set_and_only_their_own_variants() {
+        let theme = library::tests::sample();
+        let mut archive = ZipArchive::new(Cursor::new(encode("Sample", &theme))).unwrap();
+        let variants: Vec<String> = archive.file_names().filter(|name| name.starts_with("variants/")).map(String::from).collect();
+        assert_eq!(variants, [format!("variants/{}.toml", theme.meta.variant_of(&theme.palette).key())]);
+        let set: Table = toml::from_str(&entry(&mut archive, "themeset.toml").unwrap()).unwrap();
+        assert_eq!(set["custom"]["application"]["background"].as_str(), Some(hex(&theme.palette.bg1).as_str()));
+        assert!(set.contains_key("light") && set.contains_key("dark"));
+    }
+
+    #[test]
+    fn names_decode_would_refuse_are_not_exported() {
+        let path = std::env::temp_dir().join(format!("tetra-test-{}-bundle.{EXTENSION}", std::process::id()));
+        assert!(export("a/b", &library::tests::sample(), &path).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+use gettextrs::gettext;
+use crate::bundle::{self, Conflict};
+use crate::code;
+use crate::compare::{self, CompareSource};
+use crate::library::Library;
+use crate::portal;
+use crate::schedule;
+use crate::theme::{self, theme_path, Theme, ThemeMeta};
+
+const USAGE: &str = "usage:
+  tetra                                     open the editor
+  tetra bundle export [<theme>] [-o <file>] pack a library theme, or the active one, into a .tetra bundle
+  tetra bundle import <file> [--keep-both | --replace]
+                                            add a bundle to the theme library
+  tetra code export [<theme>]               print a library theme, or the active one, as a theme code
+  tetra code import <code> [<name>]         add the theme in a theme code to the library
+  tetra daemon [--once]                     follow the light/dark schedule in ~/Oceania/tetra/schedule.toml,
+                                            or apply it once and exit
+  tetra diff <theme> <theme>                compare two palettes; each is a library theme, a file,
+                                            or saved / light / dark for the active theme and its variants
+  tetra portal serve                        publish the active variant and accent as the desktop's appearance
+                                            settings; set TETRA_DBUS_ADDRESS to use a private bus
+  tetra portal follow [--once]              switch between the saved variants as the desktop's preference changes
+  tetra portal file                         print the xdg-desktop-portal description for `portal serve`
+  tetra help                                show this message";
+
+/// runs a command line invocation, returning the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let result = match args[0].as_str() {
+        "bundle" => bundle(&args[1..]),
+        "code" => theme_code(&args[1..]),
+        "daemon" => daemon(&args[1..]),
+        "diff" => diff(&args[1..]),
+        "portal" => portal(&args[1..]),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        other => Err(format!("{} {other}\n\n{USAGE}", gettext("Unknown command:"))),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("tetra: {error}");
+            1
+        }
+    }
+}
+
+/// splits `args` into positional arguments and the value following `flag`
+fn option<'a>(args: &'a [String], flag: &str) -> Result<(Vec<&'a String>, Option<&'a String>), String> {
+    let mut positional = vec![];
+    let mut value = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            value = Some(iter.next().ok_or(format!("{} {flag}", gettext("Missing value after")))?);
+        } else {
+            positional.push(arg);
+        }
+    }
+    Ok((positional, value))
+}
+
+fn bundle(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("export") => {
+            let (positional, output) = option(&args[1..], "-o")?;
+            let (name, theme) = match positional.first() {
+                Some(name) => (name.to_string(), Library::home().load(name)?),
+                None => {
+                    let theme = theme::load(&theme_path(), &Library::home())?;
+                    (theme.meta.display_name().to_string(), theme)
+                }
+            };
+            let path = output.map(PathBuf::from).unwrap_or_else(|| bundle::default_path(&name));
+            bundle::export(&name, &theme, &path)?;
+            println!("{}", path.display());
+            Ok(())
+        }
+        Some("import") => {
+            let mut conflict = Conflict::Fail;
+            let mut files = vec![];
+            for arg in &args[1..] {
+                match arg.as_str() {
+                    "--keep-both" => conflict = Conflict::KeepBoth,
+                    "--replace" => conflict = Conflict::Replace,
+                    _ => files.push(arg),
+                }
+            }
+            if files.is_empty() {
+                return Err(USAGE.to_string());
+            }
+            for file in files {
+                let bundle = bundle::read(&PathBuf::from(file))?;
+                if conflict == Conflict::Fail && Library::home().exists(&bundle.name) {
+                    return Err(format!("{} {}\n{}", gettext("A theme with that name already exists:"), bundle.name, gettext("Pass --keep-both or --replace to import it anyway")));
+                }
+                println!("{}", bundle::install(&bundle, conflict)?);
+            }
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn theme_code(args: &[String]) -> Result<(), String> {
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("export"), name) => {
+            let theme = match name {
+                Some(name) => Library::home().load(name)?,
+                None => theme::load(&theme_path(), &Library::home())?,
+            };
+            println!("{}", code::encode(&theme.palette));
+            Ok(())
+        }
+        (Some("import"), Some(text)) => {
+            let palette = code::decode(text)?;
+            let name = args.get(2).cloned().unwrap_or(gettext("Shared theme"));
+            let theme = Theme { palette, meta: ThemeMeta::default(), extends: None };
+            println!("{}", Library::home().create(&name, &theme)?);
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn daemon(args: &[String]) -> Result<(), String> {
+    match args {
+        [] => schedule::run(),
+        [once] if once == "--once" => schedule::apply(None).map(|_| ()),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn diff(args: &[String]) -> Result<(), String> {
+    let [left, right] = args else {
+        return Err(USAGE.to_string());
+    };
+    let (left_source, right_source) = (CompareSource::parse(left), CompareSource::parse(right));
+    let diffs = compare::compare(&left_source.load()?, &right_source.load()?);
+    print!("{}", compare::text_diff(left, right, &diffs));
+    Ok(())
+}
+
+fn portal(args: &[String]) -> Result<(), String> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["serve"] => portal::serve(),
+        ["follow"] => portal::follow(false),
+        ["follow", "--once"] => portal::follow(true),
+        ["file"] => {
+            print!("{}", portal::PORTAL_FILE);
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
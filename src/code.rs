@@ -0,0 +1,134 @@
+use gettextrs::gettext;
+use iced::Color;
+use crate::palette::{ColorSlot, ThemePalette};
+
+/// `tetra<version>:` followed by base64url of the packed colors and a checksum
+const PREFIX: &str = "tetra";
+const VERSION: u8 = 1;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// fletcher-16 over the version byte and the payload
+fn checksum(version: u8, bytes: &[u8]) -> [u8; 2] {
+    let (mut a, mut b) = (0u16, 0u16);
+    for byte in std::iter::once(&version).chain(bytes) {
+        a = (a + *byte as u16) % 255;
+        b = (b + a) % 255;
+    }
+    [b as u8, a as u8]
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+fn from_base64url(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u32> = text.bytes().map(|c| ALPHABET.iter().position(|a| *a == c).map(|i| i as u32)).collect::<Option<_>>()?;
+    let mut out = vec![];
+    for chunk in digits.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, digit)| n | digit << (18 - 6 * i));
+        // the bits past the last byte are always zero, so every code has exactly one spelling
+        if n & (0xffffff >> (8 * (chunk.len() - 1))) != 0 {
+            return None;
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+pub fn encode(palette: &ThemePalette) -> String {
+    let bytes: Vec<u8> = ColorSlot::ALL.into_iter().flat_map(|slot| {
+        let rgba = palette.get(slot).into_rgba8();
+        [rgba[0], rgba[1], rgba[2]]
+    }).collect();
+    let sum = checksum(VERSION, &bytes);
+    format!("{PREFIX}{VERSION}:{}", base64url(&[bytes.as_slice(), &sum].concat()))
+}
+
+pub fn decode(code: &str) -> Result<ThemePalette, String> {
+    let code: String = code.split_whitespace().collect();
+    let (version, data) = code.strip_prefix(PREFIX).and_then(|rest| rest.split_once(':'))
+        .ok_or(gettext("Not a Tetra theme code"))?;
+    let version: u8 = version.parse().map_err(|_| gettext("Not a Tetra theme code"))?;
+    if version > VERSION {
+        return Err(gettext("The theme code was made by a newer version of Tetra"));
+    }
+    if version != VERSION {
+        return Err(gettext("Not a Tetra theme code"));
+    }
+    let corrupted = || gettext("The theme code is damaged, check that it was copied completely");
+    let bytes = from_base64url(data).ok_or_else(corrupted)?;
+    if bytes.len() != ColorSlot::ALL.len() * 3 + 2 {
+        return Err(corrupted());
+    }
+    let (colors, sum) = bytes.split_at(bytes.len() - 2);
+    if checksum(version, colors) != sum {
+        return Err(corrupted());
+    }
+    let mut palette = crate::presets::CATPPUCCIN_MACCHIATO.palette();
+    for (slot, rgb) in ColorSlot::ALL.into_iter().zip(colors.chunks(3)) {
+        palette.set(slot, Color::from_rgb8(rgb[0], rgb[1], rgb[2]));
+    }
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets;
+
+    #[test]
+    fn round_trip() {
+        for preset in presets::PRESETS {
+            let code = encode(&preset.palette());
+            assert!(code.starts_with("tetra1:"));
+            assert_eq!(decode(&code), Ok(preset.palette()));
+        }
+    }
+
+    #[test]
+    fn any_changed_character_is_rejected() {
+        let code = encode(&presets::PRESETS[0].palette());
+        let start = code.find(':').unwrap() + 1;
+        for position in start..code.len() {
+            for replacement in ALPHABET.iter().map(|c| *c as char).filter(|c| *c != code.as_bytes()[position] as char) {
+                let mut damaged = code.clone();
+                damaged.replace_range(position..position + 1, &replacement.to_string());
+                assert!(decode(&damaged).is_err(), "{damaged}");
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_length_or_version_is_rejected() {
+        let code = encode(&presets::PRESETS[0].palette());
+        assert!(decode(&code[..code.len() - 4]).is_err());
+        assert!(decode(&format!("{code}AAAA")).is_err());
+        assert!(decode(&code[..code.len() - 1]).is_err());
+        assert_eq!(decode(&code.replacen("tetra1:", "tetra2:", 1)), Err(gettext("The theme code was made by a newer version of Tetra")));
+        assert_eq!(decode(&code.replacen("tetra1:", "tetra0:", 1)), Err(gettext("Not a Tetra theme code")));
+        assert_eq!(decode(&code.replacen("tetra1:", "tetrax:", 1)), Err(gettext("Not a Tetra theme code")));
+        assert_eq!(decode(&code.replacen("tetra1:", "", 1)), Err(gettext("Not a Tetra theme code")));
+    }
+
+    #[test]
+    fn whitespace_is_ignored() {
+        let palette = presets::PRESETS[0].palette();
+        let code = encode(&palette);
+        let (head, tail) = code.split_at(20);
+        assert_eq!(decode(&format!("  {head}\n  {tail} \t")), Ok(palette));
+        assert!(decode("").is_err());
+        assert!(decode(" \n ").is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use iced::Color;
+
+/// `#rrggbb`, as most config formats expect
+pub fn hex(color: &Color) -> String {
+    format!("#{}", hex_bare(color))
+}
+/// `rrggbb` without a leading `#`
+pub fn hex_bare(color: &Color) -> String {
+    let rgba = color.into_rgba8();
+    format!("{:02x}{:02x}{:02x}", rgba[0], rgba[1], rgba[2])
+}
+/// `#rrggbbaa` with the given opacity
+pub fn hex_alpha(color: &Color, alpha: f32) -> String {
+    let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{}{:02x}", hex_bare(color), alpha)
+}
+pub fn rgb8(color: &Color) -> [u8; 3] {
+    let rgba = color.into_rgba8();
+    [rgba[0], rgba[1], rgba[2]]
+}
+/// linear blend from `a` towards `b`, `amount` being the share of `b`
+pub fn mix(a: Color, b: Color, amount: f32) -> Color {
+    let t = amount.clamp(0.0, 1.0);
+    Color::from_rgb(
+        a.r + (b.r - a.r) * t,
+        a.g + (b.g - a.g) * t,
+        a.b + (b.b - a.b) * t,
+    )
+}
+pub fn lighten(color: Color, amount: f32) -> Color {
+    mix(color, Color::WHITE, amount)
+}
+pub fn darken(color: Color, amount: f32) -> Color {
+    mix(color, Color::BLACK, amount)
+}
+/// WCAG relative luminance
+pub fn luminance(color: &Color) -> f32 {
+    fn channel(c: f32) -> f32 {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+pub fn is_dark(color: &Color) -> bool {
+    luminance(color) < 0.18
+}
+/// moves a color away from its own lightness, for hover and pressed states
+pub fn emphasize(color: Color, amount: f32) -> Color {
+    if is_dark(&color) {
+        lighten(color, amount)
+    } else {
+        darken(color, amount)
+    }
+}
+
+fn to_linear(c: f32) -> f64 {
+    let c = c as f64;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+fn from_linear(c: f64) -> f32 {
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    c.clamp(0.0, 1.0) as f32
+}
+/// OKLab `[L, a, b]`, where distances roughly match perceived differences
+pub fn to_oklab(color: &Color) -> [f32; 3] {
+    let (r, g, b) = (to_linear(color.r), to_linear(color.g), to_linear(color.b));
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        (0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s) as f32,
+        (1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s) as f32,
+        (0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s) as f32,
+    ]
+}
+/// back from OKLab, clipping anything outside of sRGB
+pub fn from_oklab(lab: [f32; 3]) -> Color {
+    let [lightness, a, b] = lab.map(|value| value as f64);
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    Color::from_rgb(
+        from_linear(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        from_linear(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        from_linear(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+    )
+}
+/// blend through OKLab, which keeps the midpoint from going muddy the way sRGB mixing does
+pub fn mix_oklab(a: Color, b: Color, amount: f32) -> Color {
+    let t = amount.clamp(0.0, 1.0);
+    let (a, b) = (to_oklab(&a), to_oklab(&b));
+    from_oklab([0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t))
+}
+/// OKLCh `[L, C, h]` with the hue in degrees
+pub fn to_oklch(color: &Color) -> [f32; 3] {
+    let [l, a, b] = to_oklab(color);
+    let hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+    [l, (a * a + b * b).sqrt(), hue]
+}
+pub fn from_oklch(lch: [f32; 3]) -> Color {
+    let hue = lch[2].to_radians();
+    from_oklab([lch[0], lch[1] * hue.cos(), lch[1] * hue.sin()])
+}
+/// shortest angle between two hues, in degrees
+pub fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+/// WCAG contrast ratio, from 1 to 21
+pub fn contrast(a: &Color, b: &Color) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+/// CIELAB `[L, a, b]` under D65
+pub fn to_lab(color: &Color) -> [f64; 3] {
+    let (r, g, b) = (to_linear(color.r), to_linear(color.g), to_linear(color.b));
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / 1.08883;
+    let f = |t: f64| if t > 216.0 / 24389.0 { t.cbrt() } else { (24389.0 / 27.0 * t + 16.0) / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+/// CIEDE2000 color difference; around 1 is just noticeable, above 10 clearly a different color
+pub fn delta_e2000(a: &Color, b: &Color) -> f32 {
+    ciede2000(to_lab(a), to_lab(b)) as f32
+}
+fn ciede2000([l1, a1, b1]: [f64; 3], [l2, a2, b2]: [f64; 3]) -> f64 {
+    let c_mean = ((a1.hypot(b1) + a2.hypot(b2)) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_mean / (c_mean + 25f64.powi(7))).sqrt());
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |b: f64, a: f64| if a == 0.0 && b == 0.0 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    let (h1, h2) = (hue(b1, a1), hue(b2, a2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 <= h1 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1 - 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_mean = (l1 + l2) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+    let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos() + 0.24 * (2.0 * h_mean).to_radians().cos()
+        + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos() - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+    let sl = 1.0 + 0.015 * (l_mean - 50.0).powi(2) / (20.0 + (l_mean - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * c_mean;
+    let sh = 1.0 + 0.015 * c_mean * t;
+    let c_mean7 = c_mean.powi(7);
+    let rt = -2.0 * (c_mean7 / (c_mean7 + 25f64.powi(7))).sqrt() * (60.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp()).to_radians().sin();
+    ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+}
+/// HSL with the hue in degrees and saturation and lightness from 0 to 1
+pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let (s, l) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let channel = |n: f32| {
+        let k = (n + hue.rem_euclid(360.0) / 30.0) % 12.0;
+        l - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    Color::from_rgb(channel(0.0), channel(8.0), channel(4.0))
+}
+/// `#rgb` or `#rrggbb` (alpha digits are ignored, the `#` may be `0x` or left out), or CSS `rgb()`,
+/// `hsl()` and `oklch()` with either commas or spaces
+pub fn parse(text: &str) -> Option<Color> {
+    let text = text.trim().to_lowercase();
+    if let Some((function, args)) = text.strip_suffix(')').and_then(|text| text.split_once('(')) {
+        let args: Vec<&str> = args.split(|c: char| c == ',' || c == '/' || c.is_whitespace()).filter(|arg| !arg.is_empty()).collect();
+        if !(3..=4).contains(&args.len()) {
+            return None;
+        }
+        // a percentage is that share of `full`
+        let number = |arg: &str, full: f32| match arg.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0 * full),
+            None => arg.trim_end_matches("deg").parse::<f32>().ok(),
+        };
+        return match function.trim() {
+            "rgb" | "rgba" => {
+                let channel = |i: usize| number(args[i], 255.0).map(|value| (value / 255.0).clamp(0.0, 1.0));
+                Some(Color::from_rgb(channel(0)?, channel(1)?, channel(2)?))
+            }
+            "hsl" | "hsla" => Some(from_hsl(number(args[0], 360.0)?, number(args[1], 100.0)? / 100.0, number(args[2], 100.0)? / 100.0)),
+            "oklch" => Some(from_oklch([number(args[0], 1.0)?, number(args[1], 0.4)?, number(args[2], 360.0)?])),
+            _ => None,
+        };
+    }
+    let digits = text.strip_prefix('#').or(text.strip_prefix("0x")).unwrap_or(&text);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: String = match digits.len() {
+        3 | 4 => digits.chars().take(3).flat_map(|c| [c, c]).collect(),
+        6 | 8 => digits[..6].to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the reference pairs from Sharma, Wu and Dalal, "The CIEDE2000 color-difference formula:
+    /// implementation notes, supplementary test data, and mathematical observations" (2005)
+    const SHARMA: [([f64; 3], [f64; 3], f64); 34] = [
+        ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+        ([50.0, 3.1571, -77.2803], [50.0, 0.0, -82.7485], 2.8615),
+        ([50.0, 2.8361, -74.0200], [50.0, 0.0, -82.7485], 3.4412),
+        ([50.0, -1.3802, -84.2814], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -1.1848, -84.8006], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, -0.9009, -85.5211], [50.0, 0.0, -82.7485], 1.0000),
+        ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+        ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0009], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0010], 7.1792),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0011], 7.2195),
+        ([50.0, 2.4900, -0.0010], [50.0, -2.4900, 0.0012], 7.2195),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0009, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0010, -2.4900], 4.8045),
+        ([50.0, -0.0010, 2.4900], [50.0, 0.0011, -2.4900], 4.7461),
+        ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+        ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+        ([50.0, 2.5, 0.0], [61.0, -5.0, 29.0], 22.8977),
+        ([50.0, 2.5, 0.0], [56.0, -27.0, -3.0], 31.9030),
+        ([50.0, 2.5, 0.0], [58.0, 24.0, 15.0], 19.4535),
+        ([50.0, 2.5, 0.0], [50.0, 3.1736, 0.5854], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2972, 0.0], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 1.8634, 0.5757], 1.0000),
+        ([50.0, 2.5, 0.0], [50.0, 3.2592, 0.3350], 1.0000),
+        ([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387], 1.2644),
+        ([63.0109, -31.0961, -5.8663], [62.8187, -29.7946, -4.0864], 1.2630),
+        ([61.2901, 3.7196, -5.3901], [61.4292, 2.2480, -4.9620], 1.8731),
+        ([35.0831, -44.1164, 3.7933], [35.0232, -40.0716, 1.5901], 1.8645),
+        ([22.7233, 20.0904, -46.6940], [23.0331, 14.9730, -42.5619], 2.0373),
+        ([36.4612, 47.8580, 18.3852], [36.2715, 50.5065, 21.2231], 1.4146),
+        ([90.8027, -2.0831, 1.4410], [91.1528, -1.6435, 0.0447], 1.4441),
+        ([90.9257, -0.5406, -0.9208], [88.6381, -0.8985, -0.7239], 1.5381),
+        ([6.7747, -0.2908, -2.4247], [5.8714, -0.0985, -2.2286], 0.6377),
+        ([2.0776, 0.0795, -1.1350], [0.9033, -0.0636, -0.5514], 0.9082),
+    ];
+
+    #[test]
+    fn ciede2000_matches_the_reference_pairs() {
+        for (i, (a, b, expected)) in SHARMA.into_iter().enumerate() {
+            assert!((ciede2000(a, b) - expected).abs() < 1e-4, "pair {}: {} != {expected}", i + 1, ciede2000(a, b));
+            assert!((ciede2000(b, a) - expected).abs() < 1e-4, "pair {} reversed", i + 1);
+        }
+    }
+
+    #[test]
+    fn identical_colors_have_no_difference() {
+        let color = Color::from_rgb8(0x89, 0xb4, 0xfa);
+        assert_eq!(delta_e2000(&color, &color), 0.0);
+    }
+}
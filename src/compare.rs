@@ -0,0 +1,109 @@
+use std::fmt;
+use gettextrs::gettext;
+use iced::Color;
+use crate::color::{contrast, delta_e2000, hex};
+use crate::library::Library;
+use crate::palette::{ColorSlot, ThemePalette, Variant};
+use crate::theme::{self, theme_path};
+
+/// below this ΔE2000 two colors are treated as unchanged
+pub const UNCHANGED: f32 = 0.5;
+
+/// somewhere a palette to compare can come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareSource {
+    /// whatever is open in the editor, saved or not
+    Current,
+    Saved,
+    Variant(Variant),
+    Library(String),
+    File(String),
+}
+
+impl CompareSource {
+    /// `Current` has to be handled by whoever holds the editor
+    pub fn load(&self) -> Result<ThemePalette, String> {
+        let library = Library::home();
+        match self {
+            CompareSource::Current => Err(gettext("Nothing is open in the editor")),
+            CompareSource::Saved => theme::load(&theme_path(), &library).map(|theme| theme.palette),
+            CompareSource::Variant(variant) => theme::load(&variant.path(), &library).map(|theme| theme.palette),
+            CompareSource::Library(name) => library.load(name).map(|theme| theme.palette),
+            CompareSource::File(path) => theme::load(path, &library).map(|theme| theme.palette),
+        }
+    }
+    /// `saved`, `light`, `dark`, an existing file, or else a library theme
+    pub fn parse(arg: &str) -> CompareSource {
+        match arg {
+            "saved" => CompareSource::Saved,
+            "light" => CompareSource::Variant(Variant::Light),
+            "dark" => CompareSource::Variant(Variant::Dark),
+            path if std::path::Path::new(path).is_file() => CompareSource::File(path.to_string()),
+            name => CompareSource::Library(name.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for CompareSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareSource::Current => write!(f, "{}", gettext("Current edits")),
+            CompareSource::Saved => write!(f, "{}", gettext("Saved theme")),
+            CompareSource::Variant(variant) => write!(f, "{} {}", gettext("Saved variant:"), variant),
+            CompareSource::Library(name) => write!(f, "{name}"),
+            CompareSource::File(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+pub struct SlotDiff {
+    pub slot: ColorSlot,
+    pub left: Color,
+    pub right: Color,
+    pub delta_e: f32,
+    /// contrast against the slot's usual partner, text for backgrounds and bg1 for everything else
+    pub left_contrast: f32,
+    pub right_contrast: f32,
+}
+
+impl SlotDiff {
+    pub fn changed(&self) -> bool {
+        self.delta_e >= UNCHANGED
+    }
+}
+
+fn partner(palette: &ThemePalette, slot: ColorSlot) -> Color {
+    match slot {
+        ColorSlot::Bg1 | ColorSlot::Bg2 | ColorSlot::Bg3 => palette.txt,
+        _ => palette.bg1,
+    }
+}
+
+pub fn compare(left: &ThemePalette, right: &ThemePalette) -> Vec<SlotDiff> {
+    ColorSlot::ALL.into_iter().map(|slot| {
+        let (l, r) = (left.get(slot), right.get(slot));
+        SlotDiff {
+            slot,
+            left: l,
+            right: r,
+            delta_e: delta_e2000(&l, &r),
+            left_contrast: contrast(&l, &partner(left, slot)),
+            right_contrast: contrast(&r, &partner(right, slot)),
+        }
+    }).collect()
+}
+
+/// a unified-diff style listing, with unchanged slots as context lines
+pub fn text_diff(left_name: &str, right_name: &str, diffs: &[SlotDiff]) -> String {
+    let mut out = format!("--- {left_name}\n+++ {right_name}\n");
+    for diff in diffs {
+        let key = diff.slot.key();
+        if diff.changed() {
+            out.push_str(&format!("- {key:<7} {}  {:>5.2}:1\n", hex(&diff.left), diff.left_contrast));
+            out.push_str(&format!("+ {key:<7} {}  {:>5.2}:1  ΔE {:.1}\n", hex(&diff.right), diff.right_contrast, diff.delta_e));
+        } else {
+            out.push_str(&format!("  {key:<7} {}  {:>5.2}:1\n", hex(&diff.left), diff.left_contrast));
+        }
+    }
+    out
+}
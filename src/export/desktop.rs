@@ -0,0 +1,116 @@
+use iced::Color;
+use crate::color::{hex, hex_alpha, hex_bare};
+use crate::palette::{ColorSlot, ThemePalette};
+
+/// `client.*` lines for sway and i3, meant to be pulled in with `include`
+pub fn sway(palette: &ThemePalette) -> String {
+    let c = |color: Color| hex(&color);
+    let muted = palette.muted();
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    out.push_str("#                       border  background  text  indicator  child_border\n");
+    let lines = [
+        ("client.focused", [palette.blue, palette.bg3, palette.txt, palette.pink, palette.blue]),
+        ("client.focused_inactive", [palette.bg3, palette.bg2, palette.txt, palette.bg3, palette.bg3]),
+        ("client.unfocused", [palette.bg2, palette.bg1, muted, palette.bg2, palette.bg2]),
+        ("client.urgent", [palette.red, palette.bg2, palette.red, palette.red, palette.red]),
+        ("client.placeholder", [palette.bg1, palette.bg1, palette.txt, palette.bg1, palette.bg1]),
+    ];
+    for (class, colors) in lines {
+        let colors: Vec<String> = colors.into_iter().map(c).collect();
+        out.push_str(&format!("{class} {}\n", colors.join(" ")));
+    }
+    out.push_str(&format!("client.background {}\n", c(palette.bg1)));
+    out
+}
+
+/// `$tetra_*` variables plus the border colors, meant to be pulled in with `source`
+pub fn hyprland(palette: &ThemePalette) -> String {
+    let rgb = |color: &Color| format!("rgb({})", hex_bare(color));
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("$tetra_{} = {}\n", slot.key(), rgb(&palette.get(slot))));
+    }
+    out.push_str("\ngeneral {\n");
+    out.push_str(&format!("    col.active_border = {} {} 45deg\n", rgb(&palette.blue), rgb(&palette.purple)));
+    out.push_str(&format!("    col.inactive_border = {}\n", rgb(&palette.bg3)));
+    out.push_str("}\n\ngroup {\n");
+    out.push_str(&format!("    col.border_active = {}\n", rgb(&palette.pink)));
+    out.push_str(&format!("    col.border_inactive = {}\n", rgb(&palette.bg3)));
+    out.push_str("}\n\ndecoration {\n");
+    out.push_str(&format!("    col.shadow = rgba({}aa)\n", hex_bare(&palette.bg1)));
+    out.push_str("}\n");
+    out
+}
+
+/// `@define-color` variables for a waybar `style.css` to `@import`
+pub fn waybar(palette: &ThemePalette) -> String {
+    let mut out = String::from("/* Generated by Tetra, edits will be overwritten */\n");
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("@define-color {} {};\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("@define-color muted {};\n", hex(&palette.muted())));
+    out
+}
+
+/// colors and urgency borders for mako to pull in with `include=`. it opens criteria sections,
+/// so the include belongs at the end of the config.
+pub fn mako(palette: &ThemePalette) -> String {
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    out.push_str(&format!("background-color={}\n", hex(&palette.bg2)));
+    out.push_str(&format!("text-color={}\n", hex(&palette.txt)));
+    out.push_str(&format!("border-color={}\n", hex(&palette.blue)));
+    out.push_str(&format!("progress-color=over {}\n", hex(&palette.bg3)));
+    out.push_str(&format!("\n[urgency=low]\nborder-color={}\n", hex(&palette.bg3)));
+    out.push_str(&format!("\n[urgency=high]\nborder-color={}\n", hex(&palette.red)));
+    out
+}
+
+/// a dunst drop-in for `dunstrc.d`
+pub fn dunst(palette: &ThemePalette) -> String {
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    out.push_str(&format!("[global]\nframe_color = \"{}\"\nseparator_color = frame\nhighlight = \"{}\"\n", hex(&palette.blue), hex(&palette.blue)));
+    let urgencies = [
+        ("urgency_low", palette.bg3),
+        ("urgency_normal", palette.blue),
+        ("urgency_critical", palette.red),
+    ];
+    for (section, frame) in urgencies {
+        out.push_str(&format!("\n[{section}]\nbackground = \"{}\"\nforeground = \"{}\"\nframe_color = \"{}\"\n", hex(&palette.bg2), hex(&palette.txt), hex(&frame)));
+    }
+    out
+}
+
+/// a `[colors]` section for fuzzel to `include`
+pub fn fuzzel(palette: &ThemePalette) -> String {
+    let c = |color: &Color| hex_alpha(color, 1.0).trim_start_matches('#').to_string();
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n[colors]\n");
+    out.push_str(&format!("background={}\n", hex_alpha(&palette.bg1, 0.95).trim_start_matches('#')));
+    out.push_str(&format!("text={}\n", c(&palette.txt)));
+    out.push_str(&format!("match={}\n", c(&palette.blue)));
+    out.push_str(&format!("selection={}\n", c(&palette.bg3)));
+    out.push_str(&format!("selection-text={}\n", c(&palette.txt)));
+    out.push_str(&format!("selection-match={}\n", c(&palette.pink)));
+    out.push_str(&format!("border={}\n", c(&palette.blue)));
+    out
+}
+
+/// a complete rofi theme, selected with `@theme "tetra"`
+pub fn rofi(palette: &ThemePalette) -> String {
+    let mut out = String::from("/* Generated by Tetra, edits will be overwritten */\n* {\n");
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("    tetra-{}: {};\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("    tetra-muted: {};\n", hex(&palette.muted())));
+    out.push_str("    background-color: transparent;\n    text-color: @tetra-txt;\n}\n\n");
+    out.push_str("window {\n    background-color: @tetra-bg1;\n    border: 2px;\n    border-color: @tetra-blue;\n    padding: 8px;\n}\n\n");
+    out.push_str("inputbar {\n    background-color: @tetra-bg2;\n    padding: 6px;\n    children: [ prompt, entry ];\n    spacing: 6px;\n}\n\n");
+    out.push_str("prompt {\n    text-color: @tetra-blue;\n}\n\n");
+    out.push_str("entry {\n    placeholder-color: @tetra-muted;\n}\n\n");
+    out.push_str("listview {\n    lines: 10;\n    padding: 6px 0px 0px;\n}\n\n");
+    out.push_str("element {\n    padding: 4px 6px;\n}\n\n");
+    out.push_str("element selected.normal {\n    background-color: @tetra-bg3;\n    text-color: @tetra-txt;\n}\n\n");
+    out.push_str("element urgent.normal {\n    text-color: @tetra-red;\n}\n\n");
+    out.push_str("element active.normal {\n    text-color: @tetra-green;\n}\n\n");
+    out.push_str("element-text, element-icon {\n    text-color: inherit;\n}\n");
+    out
+}
@@ -0,0 +1,401 @@
+use iced::Color;
+use oceania_style::get_home;
+use serde_derive::{Serialize, Deserialize};
+use serde_json::json;
+use crate::color::{hex, hex_alpha, is_dark, mix};
+use crate::palette::{ColorSlot, ThemePalette, Variant};
+use crate::theme::ThemeMeta;
+
+/// which slot each syntax role is drawn in, read from `~/Oceania/tetra/syntax.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyntaxMapping {
+    pub keyword: ColorSlot,
+    pub function: ColorSlot,
+    pub string: ColorSlot,
+    pub constant: ColorSlot,
+    pub number: ColorSlot,
+    #[serde(rename = "type")]
+    pub type_name: ColorSlot,
+    pub variable: ColorSlot,
+    pub operator: ColorSlot,
+    pub tag: ColorSlot,
+    pub attribute: ColorSlot,
+    /// drawn muted towards the background rather than at full strength
+    pub comment: ColorSlot,
+    pub error: ColorSlot,
+    pub warning: ColorSlot,
+    pub info: ColorSlot,
+    pub hint: ColorSlot,
+}
+
+impl Default for SyntaxMapping {
+    fn default() -> Self {
+        SyntaxMapping {
+            keyword: ColorSlot::Purple,
+            function: ColorSlot::Blue,
+            string: ColorSlot::Green,
+            constant: ColorSlot::Orange,
+            number: ColorSlot::Orange,
+            type_name: ColorSlot::Yellow,
+            variable: ColorSlot::Txt,
+            operator: ColorSlot::Pink,
+            tag: ColorSlot::Blue,
+            attribute: ColorSlot::Yellow,
+            comment: ColorSlot::Txt,
+            error: ColorSlot::Red,
+            warning: ColorSlot::Yellow,
+            info: ColorSlot::Blue,
+            hint: ColorSlot::Green,
+        }
+    }
+}
+
+impl SyntaxMapping {
+    pub fn load() -> SyntaxMapping {
+        let path = format!("{}/Oceania/tetra/syntax.toml", get_home());
+        match std::fs::read_to_string(path) {
+            Ok(value) => toml::from_str(&value).unwrap_or_default(),
+            Err(..) => SyntaxMapping::default()
+        }
+    }
+    fn comment_color(&self, palette: &ThemePalette) -> Color {
+        mix(palette.get(self.comment), palette.bg3, 0.45)
+    }
+}
+
+fn selection_color(palette: &ThemePalette) -> Color {
+    mix(palette.blue, palette.bg1, 0.7)
+}
+
+pub fn neovim(palette: &ThemePalette, mapping: &SyntaxMapping) -> String {
+    let background = if is_dark(&palette.bg1) { "dark" } else { "light" };
+    let mut out = String::from("-- Generated by Tetra, edits will be overwritten\n");
+    out.push_str("vim.cmd(\"highlight clear\")\n");
+    out.push_str("if vim.fn.exists(\"syntax_on\") == 1 then\n  vim.cmd(\"syntax reset\")\nend\n");
+    out.push_str(&format!("vim.o.background = \"{background}\"\n"));
+    out.push_str("vim.g.colors_name = \"tetra\"\n\n");
+
+    out.push_str("local c = {\n");
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("  {} = \"{}\",\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("  comment = \"{}\",\n", hex(&mapping.comment_color(palette))));
+    out.push_str(&format!("  selection = \"{}\",\n", hex(&selection_color(palette))));
+    out.push_str("}\n\n");
+    out.push_str("local function hl(group, opts)\n  vim.api.nvim_set_hl(0, group, opts)\nend\n\n");
+
+    let fg = |slot: ColorSlot| format!("{{ fg = c.{} }}", slot.key());
+    let groups: Vec<(&str, String)> = vec![
+        ("Normal", "{ fg = c.txt, bg = c.bg1 }".into()),
+        ("NormalFloat", "{ fg = c.txt, bg = c.bg2 }".into()),
+        ("FloatBorder", "{ fg = c.bg3, bg = c.bg2 }".into()),
+        ("NormalNC", "{ fg = c.txt, bg = c.bg1 }".into()),
+        ("Cursor", "{ fg = c.bg1, bg = c.txt }".into()),
+        ("CursorLine", "{ bg = c.bg2 }".into()),
+        ("CursorLineNr", format!("{{ fg = c.{}, bold = true }}", mapping.keyword.key())),
+        ("LineNr", "{ fg = c.comment }".into()),
+        ("SignColumn", "{ bg = c.bg1 }".into()),
+        ("ColorColumn", "{ bg = c.bg2 }".into()),
+        ("Visual", "{ bg = c.selection }".into()),
+        ("Search", "{ fg = c.bg1, bg = c.yellow }".into()),
+        ("IncSearch", "{ fg = c.bg1, bg = c.orange }".into()),
+        ("MatchParen", "{ fg = c.orange, bold = true }".into()),
+        ("StatusLine", "{ fg = c.txt, bg = c.bg3 }".into()),
+        ("StatusLineNC", "{ fg = c.comment, bg = c.bg2 }".into()),
+        ("TabLine", "{ fg = c.comment, bg = c.bg2 }".into()),
+        ("TabLineSel", "{ fg = c.txt, bg = c.bg3 }".into()),
+        ("TabLineFill", "{ bg = c.bg1 }".into()),
+        ("WinSeparator", "{ fg = c.bg3 }".into()),
+        ("VertSplit", "{ fg = c.bg3 }".into()),
+        ("Pmenu", "{ fg = c.txt, bg = c.bg2 }".into()),
+        ("PmenuSel", "{ fg = c.bg1, bg = c.blue }".into()),
+        ("PmenuSbar", "{ bg = c.bg3 }".into()),
+        ("PmenuThumb", "{ bg = c.comment }".into()),
+        ("Folded", "{ fg = c.comment, bg = c.bg2 }".into()),
+        ("NonText", "{ fg = c.bg3 }".into()),
+        ("Whitespace", "{ fg = c.bg3 }".into()),
+        ("Title", "{ fg = c.blue, bold = true }".into()),
+        ("Directory", "{ fg = c.blue }".into()),
+        ("Comment", "{ fg = c.comment, italic = true }".into()),
+        ("Constant", fg(mapping.constant)),
+        ("String", fg(mapping.string)),
+        ("Character", fg(mapping.string)),
+        ("Number", fg(mapping.number)),
+        ("Boolean", fg(mapping.constant)),
+        ("Float", fg(mapping.number)),
+        ("Identifier", fg(mapping.variable)),
+        ("Function", fg(mapping.function)),
+        ("Statement", fg(mapping.keyword)),
+        ("Conditional", fg(mapping.keyword)),
+        ("Repeat", fg(mapping.keyword)),
+        ("Label", fg(mapping.keyword)),
+        ("Keyword", fg(mapping.keyword)),
+        ("Exception", fg(mapping.keyword)),
+        ("Operator", fg(mapping.operator)),
+        ("PreProc", fg(mapping.attribute)),
+        ("Include", fg(mapping.keyword)),
+        ("Define", fg(mapping.keyword)),
+        ("Macro", fg(mapping.attribute)),
+        ("Type", fg(mapping.type_name)),
+        ("StorageClass", fg(mapping.keyword)),
+        ("Structure", fg(mapping.type_name)),
+        ("Typedef", fg(mapping.type_name)),
+        ("Special", fg(mapping.operator)),
+        ("Tag", fg(mapping.tag)),
+        ("Delimiter", "{ fg = c.txt }".into()),
+        ("Todo", "{ fg = c.bg1, bg = c.yellow, bold = true }".into()),
+        ("Error", fg(mapping.error)),
+        ("ErrorMsg", fg(mapping.error)),
+        ("WarningMsg", fg(mapping.warning)),
+        ("DiagnosticError", fg(mapping.error)),
+        ("DiagnosticWarn", fg(mapping.warning)),
+        ("DiagnosticInfo", fg(mapping.info)),
+        ("DiagnosticHint", fg(mapping.hint)),
+        ("DiagnosticUnderlineError", format!("{{ undercurl = true, sp = c.{} }}", mapping.error.key())),
+        ("DiagnosticUnderlineWarn", format!("{{ undercurl = true, sp = c.{} }}", mapping.warning.key())),
+        ("DiagnosticUnderlineInfo", format!("{{ undercurl = true, sp = c.{} }}", mapping.info.key())),
+        ("DiagnosticUnderlineHint", format!("{{ undercurl = true, sp = c.{} }}", mapping.hint.key())),
+        ("DiffAdd", "{ fg = c.green }".into()),
+        ("DiffChange", "{ fg = c.yellow }".into()),
+        ("DiffDelete", "{ fg = c.red }".into()),
+        ("DiffText", "{ fg = c.blue, bold = true }".into()),
+        ("@variable", fg(mapping.variable)),
+        ("@property", fg(mapping.variable)),
+        ("@parameter", fg(mapping.variable)),
+        ("@function", fg(mapping.function)),
+        ("@function.builtin", fg(mapping.function)),
+        ("@method", fg(mapping.function)),
+        ("@constructor", fg(mapping.type_name)),
+        ("@keyword", fg(mapping.keyword)),
+        ("@keyword.function", fg(mapping.keyword)),
+        ("@string", fg(mapping.string)),
+        ("@number", fg(mapping.number)),
+        ("@boolean", fg(mapping.constant)),
+        ("@constant", fg(mapping.constant)),
+        ("@constant.builtin", fg(mapping.constant)),
+        ("@type", fg(mapping.type_name)),
+        ("@type.builtin", fg(mapping.type_name)),
+        ("@operator", fg(mapping.operator)),
+        ("@attribute", fg(mapping.attribute)),
+        ("@tag", fg(mapping.tag)),
+        ("@tag.attribute", fg(mapping.attribute)),
+        ("@comment", "{ link = \"Comment\" }".into()),
+        ("@punctuation", "{ fg = c.txt }".into()),
+    ];
+    for (group, opts) in groups {
+        out.push_str(&format!("hl(\"{group}\", {opts})\n"));
+    }
+    out.push('\n');
+    for (i, color) in palette.ansi().iter().enumerate() {
+        out.push_str(&format!("vim.g.terminal_color_{i} = \"{}\"\n", hex(color)));
+    }
+    out
+}
+
+pub fn helix(palette: &ThemePalette, mapping: &SyntaxMapping) -> String {
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    let scopes: Vec<(&str, String)> = vec![
+        ("ui.background", "{ bg = \"bg1\" }".into()),
+        ("ui.text", "\"txt\"".into()),
+        ("ui.text.focus", "{ fg = \"txt\", bg = \"bg3\" }".into()),
+        ("ui.selection", "{ bg = \"selection\" }".into()),
+        ("ui.cursor", "{ fg = \"bg1\", bg = \"txt\" }".into()),
+        ("ui.cursor.match", "{ fg = \"orange\", modifiers = [\"bold\"] }".into()),
+        ("ui.cursorline.primary", "{ bg = \"bg2\" }".into()),
+        ("ui.linenr", "\"comment\"".into()),
+        ("ui.linenr.selected", format!("\"{}\"", mapping.keyword.key())),
+        ("ui.statusline", "{ fg = \"txt\", bg = \"bg3\" }".into()),
+        ("ui.statusline.inactive", "{ fg = \"comment\", bg = \"bg2\" }".into()),
+        ("ui.statusline.normal", "{ fg = \"bg1\", bg = \"blue\" }".into()),
+        ("ui.statusline.insert", "{ fg = \"bg1\", bg = \"green\" }".into()),
+        ("ui.statusline.select", "{ fg = \"bg1\", bg = \"purple\" }".into()),
+        ("ui.popup", "{ fg = \"txt\", bg = \"bg2\" }".into()),
+        ("ui.window", "\"bg3\"".into()),
+        ("ui.help", "{ fg = \"txt\", bg = \"bg2\" }".into()),
+        ("ui.menu", "{ fg = \"txt\", bg = \"bg2\" }".into()),
+        ("ui.menu.selected", "{ fg = \"bg1\", bg = \"blue\" }".into()),
+        ("ui.virtual.whitespace", "\"bg3\"".into()),
+        ("ui.virtual.ruler", "{ bg = \"bg2\" }".into()),
+        ("ui.virtual.inlay-hint", "\"comment\"".into()),
+        ("comment", "{ fg = \"comment\", modifiers = [\"italic\"] }".into()),
+        ("keyword", format!("\"{}\"", mapping.keyword.key())),
+        ("function", format!("\"{}\"", mapping.function.key())),
+        ("string", format!("\"{}\"", mapping.string.key())),
+        ("constant", format!("\"{}\"", mapping.constant.key())),
+        ("constant.numeric", format!("\"{}\"", mapping.number.key())),
+        ("type", format!("\"{}\"", mapping.type_name.key())),
+        ("constructor", format!("\"{}\"", mapping.type_name.key())),
+        ("variable", format!("\"{}\"", mapping.variable.key())),
+        ("operator", format!("\"{}\"", mapping.operator.key())),
+        ("punctuation", "\"txt\"".into()),
+        ("attribute", format!("\"{}\"", mapping.attribute.key())),
+        ("tag", format!("\"{}\"", mapping.tag.key())),
+        ("namespace", format!("\"{}\"", mapping.type_name.key())),
+        ("label", format!("\"{}\"", mapping.keyword.key())),
+        ("special", format!("\"{}\"", mapping.operator.key())),
+        ("markup.heading", "{ fg = \"blue\", modifiers = [\"bold\"] }".into()),
+        ("markup.bold", "{ modifiers = [\"bold\"] }".into()),
+        ("markup.italic", "{ modifiers = [\"italic\"] }".into()),
+        ("markup.link.url", "{ fg = \"blue\", modifiers = [\"underlined\"] }".into()),
+        ("markup.raw", format!("\"{}\"", mapping.string.key())),
+        ("diff.plus", "\"green\"".into()),
+        ("diff.minus", "\"red\"".into()),
+        ("diff.delta", "\"yellow\"".into()),
+        ("error", format!("\"{}\"", mapping.error.key())),
+        ("warning", format!("\"{}\"", mapping.warning.key())),
+        ("info", format!("\"{}\"", mapping.info.key())),
+        ("hint", format!("\"{}\"", mapping.hint.key())),
+        ("diagnostic.error", format!("{{ underline = {{ color = \"{}\", style = \"curl\" }} }}", mapping.error.key())),
+        ("diagnostic.warning", format!("{{ underline = {{ color = \"{}\", style = \"curl\" }} }}", mapping.warning.key())),
+        ("diagnostic.info", format!("{{ underline = {{ color = \"{}\", style = \"curl\" }} }}", mapping.info.key())),
+        ("diagnostic.hint", format!("{{ underline = {{ color = \"{}\", style = \"curl\" }} }}", mapping.hint.key())),
+    ];
+    for (scope, style) in scopes {
+        out.push_str(&format!("\"{scope}\" = {style}\n"));
+    }
+    out.push_str("\n[palette]\n");
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("{} = \"{}\"\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("comment = \"{}\"\n", hex(&mapping.comment_color(palette))));
+    out.push_str(&format!("selection = \"{}\"\n", hex(&selection_color(palette))));
+    out
+}
+
+pub fn vscode_manifest(palette: &ThemePalette, meta: &ThemeMeta) -> String {
+    let ui_theme = match meta.variant_of(palette) {
+        Variant::Dark => "vs-dark",
+        Variant::Light => "vs",
+    };
+    let description = if meta.description.is_empty() { "Generated by Tetra, edits will be overwritten" } else { &meta.description };
+    let version = if meta.version.is_empty() { "0.1.0" } else { &meta.version };
+    let mut manifest = json!({
+        "name": "tetra-theme",
+        "displayName": meta.display_name(),
+        "description": description,
+        "version": version,
+        "engines": { "vscode": "^1.70.0" },
+        "categories": ["Themes"],
+        "contributes": {
+            "themes": [{
+                "label": meta.display_name(),
+                "uiTheme": ui_theme,
+                "path": "./themes/tetra-color-theme.json"
+            }]
+        }
+    });
+    if !meta.author.is_empty() {
+        manifest["author"] = json!(meta.author);
+    }
+    if !meta.license.is_empty() {
+        manifest["license"] = json!(meta.license);
+    }
+    if !meta.tags.is_empty() {
+        manifest["keywords"] = json!(meta.tags);
+    }
+    serde_json::to_string_pretty(&manifest).unwrap()
+}
+
+pub fn vscode(palette: &ThemePalette, mapping: &SyntaxMapping) -> String {
+    let c = |slot: ColorSlot| hex(&palette.get(slot));
+    let comment = hex(&mapping.comment_color(palette));
+    let ansi = palette.ansi();
+    let mut colors = serde_json::Map::new();
+    let workbench = [
+        ("editor.background", c(ColorSlot::Bg1)),
+        ("editor.foreground", c(ColorSlot::Txt)),
+        ("editorCursor.foreground", c(ColorSlot::Txt)),
+        ("editor.selectionBackground", hex(&selection_color(palette))),
+        ("editor.lineHighlightBackground", c(ColorSlot::Bg2)),
+        ("editor.findMatchBackground", hex_alpha(&palette.yellow, 0.4)),
+        ("editorLineNumber.foreground", comment.clone()),
+        ("editorLineNumber.activeForeground", c(mapping.keyword)),
+        ("editorWhitespace.foreground", c(ColorSlot::Bg3)),
+        ("editorIndentGuide.background", c(ColorSlot::Bg3)),
+        ("editorWidget.background", c(ColorSlot::Bg2)),
+        ("editorError.foreground", c(mapping.error)),
+        ("editorWarning.foreground", c(mapping.warning)),
+        ("editorInfo.foreground", c(mapping.info)),
+        ("editorHint.foreground", c(mapping.hint)),
+        ("editorGutter.addedBackground", c(ColorSlot::Green)),
+        ("editorGutter.modifiedBackground", c(ColorSlot::Yellow)),
+        ("editorGutter.deletedBackground", c(ColorSlot::Red)),
+        ("activityBar.background", c(ColorSlot::Bg2)),
+        ("activityBar.foreground", c(ColorSlot::Txt)),
+        ("activityBarBadge.background", c(ColorSlot::Blue)),
+        ("activityBarBadge.foreground", c(ColorSlot::Bg1)),
+        ("sideBar.background", c(ColorSlot::Bg2)),
+        ("sideBar.foreground", c(ColorSlot::Txt)),
+        ("sideBarSectionHeader.background", c(ColorSlot::Bg3)),
+        ("statusBar.background", c(ColorSlot::Bg3)),
+        ("statusBar.foreground", c(ColorSlot::Txt)),
+        ("titleBar.activeBackground", c(ColorSlot::Bg2)),
+        ("titleBar.activeForeground", c(ColorSlot::Txt)),
+        ("titleBar.inactiveBackground", c(ColorSlot::Bg2)),
+        ("titleBar.inactiveForeground", comment.clone()),
+        ("tab.activeBackground", c(ColorSlot::Bg1)),
+        ("tab.activeForeground", c(ColorSlot::Txt)),
+        ("tab.inactiveBackground", c(ColorSlot::Bg2)),
+        ("tab.inactiveForeground", comment.clone()),
+        ("editorGroupHeader.tabsBackground", c(ColorSlot::Bg2)),
+        ("panel.background", c(ColorSlot::Bg2)),
+        ("panel.border", c(ColorSlot::Bg3)),
+        ("focusBorder", c(ColorSlot::Blue)),
+        ("button.background", c(ColorSlot::Blue)),
+        ("button.foreground", c(ColorSlot::Bg1)),
+        ("input.background", c(ColorSlot::Bg2)),
+        ("input.foreground", c(ColorSlot::Txt)),
+        ("dropdown.background", c(ColorSlot::Bg2)),
+        ("list.activeSelectionBackground", c(ColorSlot::Bg3)),
+        ("list.hoverBackground", c(ColorSlot::Bg2)),
+        ("gitDecoration.addedResourceForeground", c(ColorSlot::Green)),
+        ("gitDecoration.modifiedResourceForeground", c(ColorSlot::Yellow)),
+        ("gitDecoration.deletedResourceForeground", c(ColorSlot::Red)),
+        ("terminal.background", c(ColorSlot::Bg1)),
+        ("terminal.foreground", c(ColorSlot::Txt)),
+    ];
+    for (key, value) in workbench {
+        colors.insert(key.to_string(), value.into());
+    }
+    let ansi_names = ["Black", "Red", "Green", "Yellow", "Blue", "Magenta", "Cyan", "White"];
+    for (i, name) in ansi_names.iter().enumerate() {
+        colors.insert(format!("terminal.ansi{name}"), hex(&ansi[i]).into());
+        colors.insert(format!("terminal.ansiBright{name}"), hex(&ansi[i + 8]).into());
+    }
+
+    let token = |scope: &[&str], foreground: String, font_style: &str| {
+        json!({
+            "scope": scope,
+            "settings": { "foreground": foreground, "fontStyle": font_style }
+        })
+    };
+    let tokens = vec![
+        token(&["comment", "punctuation.definition.comment"], comment.clone(), "italic"),
+        token(&["keyword", "storage.type", "storage.modifier", "keyword.control"], c(mapping.keyword), ""),
+        token(&["entity.name.function", "support.function", "meta.function-call"], c(mapping.function), ""),
+        token(&["string", "string.quoted", "string.template"], c(mapping.string), ""),
+        token(&["constant", "constant.language", "support.constant"], c(mapping.constant), ""),
+        token(&["constant.numeric"], c(mapping.number), ""),
+        token(&["entity.name.type", "entity.name.class", "support.type", "support.class"], c(mapping.type_name), ""),
+        token(&["variable", "variable.parameter", "variable.other"], c(mapping.variable), ""),
+        token(&["keyword.operator", "punctuation.accessor"], c(mapping.operator), ""),
+        token(&["entity.name.tag"], c(mapping.tag), ""),
+        token(&["entity.other.attribute-name", "meta.attribute"], c(mapping.attribute), ""),
+        token(&["invalid", "invalid.illegal"], c(mapping.error), ""),
+        token(&["markup.heading"], c(ColorSlot::Blue), "bold"),
+        token(&["markup.bold"], c(ColorSlot::Txt), "bold"),
+        token(&["markup.italic"], c(ColorSlot::Txt), "italic"),
+        token(&["markup.inserted"], c(ColorSlot::Green), ""),
+        token(&["markup.deleted"], c(ColorSlot::Red), ""),
+        token(&["markup.changed"], c(ColorSlot::Yellow), ""),
+    ];
+
+    let theme = json!({
+        "name": "Tetra",
+        "type": if is_dark(&palette.bg1) { "dark" } else { "light" },
+        "colors": colors,
+        "tokenColors": tokens,
+    });
+    serde_json::to_string_pretty(&theme).unwrap()
+}
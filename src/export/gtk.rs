@@ -0,0 +1,87 @@
+use iced::Color;
+use crate::color::{darken, hex, is_dark, lighten, mix};
+use crate::palette::ThemePalette;
+
+fn define(out: &mut String, name: &str, color: &Color) {
+    out.push_str(&format!("@define-color {name} {};\n", hex(color)));
+}
+
+/// libadwaita's named colors, shared by gtk 4 and adw-gtk3 style gtk 3 themes
+fn named_colors(out: &mut String, palette: &ThemePalette) {
+    let dark = is_dark(&palette.bg1);
+    let on = |color: &Color| if is_dark(color) { Color::WHITE } else { Color::BLACK };
+    let shade = if dark { darken(palette.bg1, 0.4) } else { darken(palette.bg1, 0.08) };
+    let card = if dark { lighten(palette.bg2, 0.03) } else { lighten(palette.bg2, 0.5) };
+
+    define(out, "accent_color", &palette.blue);
+    define(out, "accent_bg_color", &palette.blue);
+    define(out, "accent_fg_color", &on(&palette.blue));
+    define(out, "destructive_color", &palette.red);
+    define(out, "destructive_bg_color", &palette.red);
+    define(out, "destructive_fg_color", &on(&palette.red));
+    define(out, "success_color", &palette.green);
+    define(out, "success_bg_color", &palette.green);
+    define(out, "success_fg_color", &on(&palette.green));
+    define(out, "warning_color", &palette.yellow);
+    define(out, "warning_bg_color", &palette.yellow);
+    define(out, "warning_fg_color", &on(&palette.yellow));
+    define(out, "error_color", &palette.red);
+    define(out, "error_bg_color", &palette.red);
+    define(out, "error_fg_color", &on(&palette.red));
+    define(out, "window_bg_color", &palette.bg2);
+    define(out, "window_fg_color", &palette.txt);
+    define(out, "view_bg_color", &palette.bg1);
+    define(out, "view_fg_color", &palette.txt);
+    define(out, "headerbar_bg_color", &palette.bg3);
+    define(out, "headerbar_fg_color", &palette.txt);
+    define(out, "headerbar_border_color", &palette.txt);
+    define(out, "headerbar_backdrop_color", &palette.bg2);
+    define(out, "headerbar_shade_color", &shade);
+    define(out, "sidebar_bg_color", &palette.bg3);
+    define(out, "sidebar_fg_color", &palette.txt);
+    define(out, "sidebar_backdrop_color", &palette.bg2);
+    define(out, "sidebar_shade_color", &shade);
+    define(out, "card_bg_color", &card);
+    define(out, "card_fg_color", &palette.txt);
+    define(out, "card_shade_color", &shade);
+    define(out, "dialog_bg_color", &palette.bg2);
+    define(out, "dialog_fg_color", &palette.txt);
+    define(out, "popover_bg_color", &palette.bg2);
+    define(out, "popover_fg_color", &palette.txt);
+    define(out, "popover_shade_color", &shade);
+    define(out, "thumbnail_bg_color", &palette.bg2);
+    define(out, "thumbnail_fg_color", &palette.txt);
+    define(out, "shade_color", &shade);
+    define(out, "scrollbar_outline_color", &palette.bg1);
+}
+
+pub fn gtk4(palette: &ThemePalette) -> String {
+    let mut out = String::from("/* Generated by Tetra, edits will be overwritten */\n");
+    named_colors(&mut out, palette);
+    out
+}
+
+/// gtk 3 also gets the legacy Adwaita names, which most gtk 3 themes still read
+pub fn gtk3(palette: &ThemePalette) -> String {
+    let mut out = gtk4(palette);
+    let muted = palette.muted();
+    out.push('\n');
+    define(&mut out, "theme_fg_color", &palette.txt);
+    define(&mut out, "theme_text_color", &palette.txt);
+    define(&mut out, "theme_bg_color", &palette.bg2);
+    define(&mut out, "theme_base_color", &palette.bg1);
+    define(&mut out, "theme_selected_bg_color", &palette.blue);
+    define(&mut out, "theme_selected_fg_color", &palette.bg1);
+    define(&mut out, "theme_unfocused_fg_color", &muted);
+    define(&mut out, "theme_unfocused_text_color", &muted);
+    define(&mut out, "theme_unfocused_bg_color", &palette.bg2);
+    define(&mut out, "theme_unfocused_base_color", &palette.bg1);
+    define(&mut out, "theme_unfocused_selected_bg_color", &mix(palette.blue, palette.bg2, 0.3));
+    define(&mut out, "theme_unfocused_selected_fg_color", &palette.bg1);
+    define(&mut out, "insensitive_bg_color", &palette.bg2);
+    define(&mut out, "insensitive_fg_color", &muted);
+    define(&mut out, "insensitive_base_color", &palette.bg1);
+    define(&mut out, "borders", &palette.bg3);
+    define(&mut out, "unfocused_borders", &palette.bg3);
+    out
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use gettextrs::gettext;
+use oceania_style::get_home;
+use crate::palette::ThemePalette;
+use crate::theme::ThemeMeta;
+use crate::wal;
+
+mod desktop;
+mod editor;
+mod gtk;
+mod qt;
+mod web;
+
+pub use editor::SyntaxMapping;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    Neovim,
+    Helix,
+    VsCode,
+    Gtk,
+    Kde,
+    QtCt,
+    Sway,
+    I3,
+    Hyprland,
+    Waybar,
+    Mako,
+    Dunst,
+    Fuzzel,
+    Rofi,
+    Css,
+    Scss,
+    Tailwind,
+    DesignTokens,
+    Xresources,
+    Pywal,
+}
+
+pub struct ExportFile {
+    pub path: PathBuf,
+    pub contents: String,
+    /// the path may hold a hand written config that should be kept around before overwriting it
+    pub backup: bool,
+}
+
+impl ExportTarget {
+    pub const ALL: [ExportTarget; 20] = [
+        ExportTarget::Neovim,
+        ExportTarget::Helix,
+        ExportTarget::VsCode,
+        ExportTarget::Gtk,
+        ExportTarget::Kde,
+        ExportTarget::QtCt,
+        ExportTarget::Sway,
+        ExportTarget::I3,
+        ExportTarget::Hyprland,
+        ExportTarget::Waybar,
+        ExportTarget::Mako,
+        ExportTarget::Dunst,
+        ExportTarget::Fuzzel,
+        ExportTarget::Rofi,
+        ExportTarget::Css,
+        ExportTarget::Scss,
+        ExportTarget::Tailwind,
+        ExportTarget::DesignTokens,
+        ExportTarget::Xresources,
+        ExportTarget::Pywal,
+    ];
+    /// the directory name used inside bundles
+    pub fn key(&self) -> &'static str {
+        match self {
+            ExportTarget::Neovim => "neovim",
+            ExportTarget::Helix => "helix",
+            ExportTarget::VsCode => "vscode",
+            ExportTarget::Gtk => "gtk",
+            ExportTarget::Kde => "kde",
+            ExportTarget::QtCt => "qtct",
+            ExportTarget::Sway => "sway",
+            ExportTarget::I3 => "i3",
+            ExportTarget::Hyprland => "hyprland",
+            ExportTarget::Waybar => "waybar",
+            ExportTarget::Mako => "mako",
+            ExportTarget::Dunst => "dunst",
+            ExportTarget::Fuzzel => "fuzzel",
+            ExportTarget::Rofi => "rofi",
+            ExportTarget::Css => "css",
+            ExportTarget::Scss => "scss",
+            ExportTarget::Tailwind => "tailwind",
+            ExportTarget::DesignTokens => "design-tokens",
+            ExportTarget::Xresources => "xresources",
+            ExportTarget::Pywal => "pywal",
+        }
+    }
+    pub fn render(&self, palette: &ThemePalette, meta: &ThemeMeta) -> Vec<ExportFile> {
+        let mapping = SyntaxMapping::load();
+        match self {
+            ExportTarget::Neovim => vec![ExportFile {
+                path: config_dir().join("nvim/colors/tetra.lua"),
+                contents: editor::neovim(palette, &mapping),
+                backup: false,
+            }],
+            ExportTarget::Helix => vec![ExportFile {
+                path: config_dir().join("helix/themes/tetra.toml"),
+                contents: editor::helix(palette, &mapping),
+                backup: false,
+            }],
+            ExportTarget::VsCode => {
+                let dir = PathBuf::from(get_home()).join(".vscode/extensions/tetra-theme");
+                vec![
+                    ExportFile {
+                        path: dir.join("package.json"),
+                        contents: editor::vscode_manifest(palette, meta),
+                        backup: false,
+                    },
+                    ExportFile {
+                        path: dir.join("themes/tetra-color-theme.json"),
+                        contents: editor::vscode(palette, &mapping),
+                        backup: false,
+                    },
+                ]
+            }
+            ExportTarget::Gtk => vec![
+                ExportFile {
+                    path: config_dir().join("gtk-3.0/gtk.css"),
+                    contents: gtk::gtk3(palette),
+                    backup: true,
+                },
+                ExportFile {
+                    path: config_dir().join("gtk-4.0/gtk.css"),
+                    contents: gtk::gtk4(palette),
+                    backup: true,
+                },
+            ],
+            ExportTarget::Kde => vec![ExportFile {
+                path: data_dir().join("color-schemes/Tetra.colors"),
+                contents: qt::kde(palette, meta),
+                backup: false,
+            }],
+            ExportTarget::QtCt => vec![
+                ExportFile {
+                    path: config_dir().join("qt5ct/colors/Tetra.conf"),
+                    contents: qt::qtct(palette),
+                    backup: false,
+                },
+                ExportFile {
+                    path: config_dir().join("qt6ct/colors/Tetra.conf"),
+                    contents: qt::qtct(palette),
+                    backup: false,
+                },
+            ],
+            ExportTarget::Sway => vec![ExportFile {
+                path: config_dir().join("sway/tetra-colors"),
+                contents: desktop::sway(palette),
+                backup: false,
+            }],
+            ExportTarget::I3 => vec![ExportFile {
+                path: config_dir().join("i3/tetra-colors"),
+                contents: desktop::sway(palette),
+                backup: false,
+            }],
+            ExportTarget::Hyprland => vec![ExportFile {
+                path: config_dir().join("hypr/tetra-colors.conf"),
+                contents: desktop::hyprland(palette),
+                backup: false,
+            }],
+            ExportTarget::Waybar => vec![ExportFile {
+                path: config_dir().join("waybar/tetra-colors.css"),
+                contents: desktop::waybar(palette),
+                backup: false,
+            }],
+            ExportTarget::Mako => vec![ExportFile {
+                path: config_dir().join("mako/tetra-colors"),
+                contents: desktop::mako(palette),
+                backup: false,
+            }],
+            ExportTarget::Dunst => vec![ExportFile {
+                path: config_dir().join("dunst/dunstrc.d/90-tetra.conf"),
+                contents: desktop::dunst(palette),
+                backup: false,
+            }],
+            ExportTarget::Fuzzel => vec![ExportFile {
+                path: config_dir().join("fuzzel/tetra-colors.ini"),
+                contents: desktop::fuzzel(palette),
+                backup: false,
+            }],
+            ExportTarget::Rofi => vec![ExportFile {
+                path: config_dir().join("rofi/themes/tetra.rasi"),
+                contents: desktop::rofi(palette),
+                backup: false,
+            }],
+            ExportTarget::Css => vec![ExportFile {
+                path: web_dir().join("tetra.css"),
+                contents: web::css(&palette.with_counterpart()),
+                backup: false,
+            }],
+            ExportTarget::Scss => vec![ExportFile {
+                path: web_dir().join("_tetra.scss"),
+                contents: web::scss(&palette.with_counterpart()),
+                backup: false,
+            }],
+            ExportTarget::Tailwind => vec![
+                ExportFile {
+                    path: web_dir().join("tailwind.tetra.js"),
+                    contents: web::tailwind_js(&palette.with_counterpart()),
+                    backup: false,
+                },
+                ExportFile {
+                    path: web_dir().join("tailwind.tetra.json"),
+                    contents: web::tailwind_json(&palette.with_counterpart()),
+                    backup: false,
+                },
+            ],
+            ExportTarget::DesignTokens => vec![ExportFile {
+                path: web_dir().join("tetra.tokens.json"),
+                contents: web::design_tokens(&palette.with_counterpart()),
+                backup: false,
+            }],
+            ExportTarget::Xresources => vec![ExportFile {
+                path: config_dir().join("X11/tetra.Xresources"),
+                contents: wal::xresources(palette),
+                backup: false,
+            }],
+            ExportTarget::Pywal => vec![
+                ExportFile {
+                    path: wal::pywal_dir().join("colors.json"),
+                    contents: wal::pywal_json(palette),
+                    backup: false,
+                },
+                ExportFile {
+                    path: wal::pywal_dir().join("colors.sh"),
+                    contents: wal::pywal_sh(palette),
+                    backup: false,
+                },
+            ],
+        }
+    }
+}
+
+impl fmt::Display for ExportTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ExportTarget::Neovim => gettext("Neovim colorscheme"),
+            ExportTarget::Helix => gettext("Helix theme"),
+            ExportTarget::VsCode => gettext("VS Code theme"),
+            ExportTarget::Gtk => gettext("GTK 3/4 colors"),
+            ExportTarget::Kde => gettext("KDE color scheme"),
+            ExportTarget::QtCt => gettext("qt5ct/qt6ct palette"),
+            ExportTarget::Sway => gettext("sway colors"),
+            ExportTarget::I3 => gettext("i3 colors"),
+            ExportTarget::Hyprland => gettext("Hyprland colors"),
+            ExportTarget::Waybar => gettext("Waybar colors"),
+            ExportTarget::Mako => gettext("Mako colors"),
+            ExportTarget::Dunst => gettext("Dunst colors"),
+            ExportTarget::Fuzzel => gettext("Fuzzel colors"),
+            ExportTarget::Rofi => gettext("Rofi theme"),
+            ExportTarget::Css => gettext("CSS custom properties"),
+            ExportTarget::Scss => gettext("SCSS map"),
+            ExportTarget::Tailwind => gettext("Tailwind colors"),
+            ExportTarget::DesignTokens => gettext("W3C design tokens"),
+            ExportTarget::Xresources => gettext("Xresources"),
+            ExportTarget::Pywal => gettext("pywal colors"),
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// `$XDG_CONFIG_HOME`, falling back to `~/.config`
+pub fn config_dir() -> PathBuf {
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(value) if !value.is_empty() => PathBuf::from(value),
+        _ => PathBuf::from(get_home()).join(".config"),
+    }
+}
+
+/// `$XDG_DATA_HOME`, falling back to `~/.local/share`
+pub fn data_dir() -> PathBuf {
+    match std::env::var("XDG_DATA_HOME") {
+        Ok(value) if !value.is_empty() => PathBuf::from(value),
+        _ => PathBuf::from(get_home()).join(".local/share"),
+    }
+}
+
+/// web exports have no fixed home, so they're collected under `~/Oceania/exports/web`
+fn web_dir() -> PathBuf {
+    PathBuf::from(get_home()).join("Oceania/exports/web")
+}
+
+/// copies a config we didn't generate ourselves to the first free `<name>.bak[.n]`
+fn backup(path: &Path) -> std::io::Result<()> {
+    let existing = match std::fs::read(path) {
+        Ok(value) => value,
+        Err(..) => return Ok(())
+    };
+    if String::from_utf8_lossy(&existing).lines().next().is_some_and(|line| line.contains("Generated by Tetra")) {
+        return Ok(());
+    }
+    let mut target = PathBuf::from(format!("{}.bak", path.display()));
+    let mut n = 1;
+    while target.exists() {
+        target = PathBuf::from(format!("{}.bak.{n}", path.display()));
+        n += 1;
+    }
+    std::fs::write(target, existing)
+}
+
+/// writes every file for `target`, returning the paths that were written
+pub fn export(target: ExportTarget, palette: &ThemePalette, meta: &ThemeMeta) -> std::io::Result<Vec<PathBuf>> {
+    let mut written = vec![];
+    for file in target.render(palette, meta) {
+        if let Some(parent) = file.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if file.backup {
+            backup(&file.path)?;
+        }
+        std::fs::write(&file.path, file.contents)?;
+        written.push(file.path);
+    }
+    Ok(written)
+}
@@ -0,0 +1,110 @@
+use iced::Color;
+use crate::color::{emphasize, hex_bare, is_dark, mix, rgb8};
+use crate::palette::ThemePalette;
+use crate::theme::ThemeMeta;
+
+fn kde_rgb(color: &Color) -> String {
+    let [r, g, b] = rgb8(color);
+    format!("{r},{g},{b}")
+}
+
+fn kde_section(out: &mut String, name: &str, background: Color, alternate: Color, foreground: Color, palette: &ThemePalette) {
+    let entries = [
+        ("BackgroundAlternate", alternate),
+        ("BackgroundNormal", background),
+        ("DecorationFocus", palette.blue),
+        ("DecorationHover", emphasize(palette.blue, 0.2)),
+        ("ForegroundActive", palette.orange),
+        ("ForegroundInactive", mix(foreground, background, 0.4)),
+        ("ForegroundLink", palette.blue),
+        ("ForegroundNegative", palette.red),
+        ("ForegroundNeutral", palette.yellow),
+        ("ForegroundNormal", foreground),
+        ("ForegroundPositive", palette.green),
+        ("ForegroundVisited", palette.purple),
+    ];
+    out.push_str(&format!("[{name}]\n"));
+    for (key, color) in entries {
+        out.push_str(&format!("{key}={}\n", kde_rgb(&color)));
+    }
+    out.push('\n');
+}
+
+/// a KDE color scheme, as read from `~/.local/share/color-schemes`
+pub fn kde(palette: &ThemePalette, meta: &ThemeMeta) -> String {
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n");
+    let disabled = mix(palette.txt, palette.bg2, 0.5);
+    out.push_str(&format!("[ColorEffects:Disabled]\nColor={}\nColorAmount=0\nColorEffect=0\nContrastAmount=0.65\nContrastEffect=1\nIntensityAmount=0.1\nIntensityEffect=2\n\n", kde_rgb(&disabled)));
+    out.push_str(&format!("[ColorEffects:Inactive]\nChangeSelectionColor=true\nColor={}\nColorAmount=0.025\nColorEffect=2\nContrastAmount=0.1\nContrastEffect=2\nEnable=false\nIntensityAmount=0\nIntensityEffect=0\n\n", kde_rgb(&palette.bg2)));
+
+    let alternate = |color: Color| emphasize(color, 0.04);
+    kde_section(&mut out, "Colors:Button", palette.bg3, alternate(palette.bg3), palette.txt, palette);
+    kde_section(&mut out, "Colors:Complementary", palette.bg1, alternate(palette.bg1), palette.txt, palette);
+    kde_section(&mut out, "Colors:Header", palette.bg3, alternate(palette.bg3), palette.txt, palette);
+    kde_section(&mut out, "Colors:Header][Inactive", palette.bg2, alternate(palette.bg2), palette.txt, palette);
+    kde_section(&mut out, "Colors:Selection", palette.blue, emphasize(palette.blue, 0.1), palette.bg1, palette);
+    kde_section(&mut out, "Colors:Tooltip", palette.bg2, alternate(palette.bg2), palette.txt, palette);
+    kde_section(&mut out, "Colors:View", palette.bg1, alternate(palette.bg1), palette.txt, palette);
+    kde_section(&mut out, "Colors:Window", palette.bg2, alternate(palette.bg2), palette.txt, palette);
+
+    out.push_str(&format!("[General]\nColorScheme=Tetra\nName={}\nshadeSortColumn=true\n\n", meta.display_name()));
+    out.push_str("[KDE]\ncontrast=4\n\n");
+    out.push_str("[WM]\n");
+    out.push_str(&format!("activeBackground={}\n", kde_rgb(&palette.bg3)));
+    out.push_str(&format!("activeBlend={}\n", kde_rgb(&palette.txt)));
+    out.push_str(&format!("activeForeground={}\n", kde_rgb(&palette.txt)));
+    out.push_str(&format!("inactiveBackground={}\n", kde_rgb(&palette.bg2)));
+    out.push_str(&format!("inactiveBlend={}\n", kde_rgb(&palette.muted())));
+    out.push_str(&format!("inactiveForeground={}\n", kde_rgb(&palette.muted())));
+    out
+}
+
+fn qt_argb(color: &Color) -> String {
+    format!("#ff{}", hex_bare(color))
+}
+
+/// the 21 colors of one `QPalette` color group, in `QPalette::ColorRole` order
+fn qt_group(palette: &ThemePalette, txt: Color, highlight: Color) -> String {
+    let dark = is_dark(&palette.bg2);
+    let (light, shadow) = if dark {
+        (emphasize(palette.bg3, 0.15), mix(palette.bg1, Color::BLACK, 0.5))
+    } else {
+        (mix(palette.bg3, Color::WHITE, 0.6), mix(palette.bg1, Color::BLACK, 0.3))
+    };
+    let roles = [
+        txt,                                // WindowText
+        palette.bg3,                        // Button
+        light,                              // Light
+        mix(light, palette.bg3, 0.5),       // Midlight
+        shadow,                             // Dark
+        mix(shadow, palette.bg3, 0.5),      // Mid
+        txt,                                // Text
+        palette.red,                        // BrightText
+        txt,                                // ButtonText
+        palette.bg1,                        // Base
+        palette.bg2,                        // Window
+        Color::BLACK,                       // Shadow
+        highlight,                          // Highlight
+        palette.bg1,                        // HighlightedText
+        palette.blue,                       // Link
+        palette.purple,                     // LinkVisited
+        emphasize(palette.bg1, 0.04),       // AlternateBase
+        palette.bg1,                        // NoRole
+        palette.bg2,                        // ToolTipBase
+        txt,                                // ToolTipText
+        mix(txt, palette.bg1, 0.5),         // PlaceholderText
+    ];
+    let colors: Vec<String> = roles.iter().map(qt_argb).collect();
+    colors.join(", ")
+}
+
+/// a qt5ct/qt6ct color scheme, as read from `~/.config/qt5ct/colors`
+pub fn qtct(palette: &ThemePalette) -> String {
+    let disabled_txt = mix(palette.txt, palette.bg2, 0.5);
+    let inactive_highlight = mix(palette.blue, palette.bg2, 0.3);
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\n[ColorScheme]\n");
+    out.push_str(&format!("active_colors={}\n", qt_group(palette, palette.txt, palette.blue)));
+    out.push_str(&format!("disabled_colors={}\n", qt_group(palette, disabled_txt, mix(palette.blue, palette.bg2, 0.6))));
+    out.push_str(&format!("inactive_colors={}\n", qt_group(palette, palette.txt, inactive_highlight)));
+    out
+}
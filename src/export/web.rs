@@ -0,0 +1,123 @@
+use serde_json::{json, Map, Value};
+use crate::color::hex;
+use crate::palette::{ColorSlot, ThemePalette, Variant};
+
+fn css_block(out: &mut String, selector: &str, palette: &ThemePalette, indent: &str) {
+    out.push_str(&format!("{indent}{selector} {{\n"));
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("{indent}  --tetra-{}: {};\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("{indent}  --tetra-muted: {};\n", hex(&palette.muted())));
+    out.push_str(&format!("{indent}}}\n"));
+}
+
+/// `:root` custom properties; with both variants the light one is the default and
+/// the dark one follows `prefers-color-scheme` or an explicit `data-theme`
+pub fn css(variants: &[(Variant, ThemePalette)]) -> String {
+    let mut out = String::from("/* Generated by Tetra, edits will be overwritten */\n");
+    match variants {
+        [(_, palette)] => css_block(&mut out, ":root", palette, ""),
+        _ => {
+            // light has to come first so the dark overrides win on equal specificity
+            let mut variants = variants.to_vec();
+            variants.sort_by_key(|(variant, _)| *variant == Variant::Dark);
+            for (variant, palette) in &variants {
+                match variant {
+                    Variant::Light => css_block(&mut out, ":root, [data-theme=\"light\"]", palette, ""),
+                    Variant::Dark => {
+                        out.push_str("\n@media (prefers-color-scheme: dark) {\n");
+                        css_block(&mut out, ":root:not([data-theme=\"light\"])", palette, "  ");
+                        out.push_str("}\n\n");
+                        css_block(&mut out, "[data-theme=\"dark\"]", palette, "");
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+fn scss_map(out: &mut String, name: &str, palette: &ThemePalette) {
+    out.push_str(&format!("${name}: (\n"));
+    for slot in ColorSlot::ALL {
+        out.push_str(&format!("  \"{}\": {},\n", slot.key(), hex(&palette.get(slot))));
+    }
+    out.push_str(&format!("  \"muted\": {},\n);\n", hex(&palette.muted())));
+}
+
+pub fn scss(variants: &[(Variant, ThemePalette)]) -> String {
+    let mut out = String::from("// Generated by Tetra, edits will be overwritten\n");
+    for (variant, palette) in variants {
+        scss_map(&mut out, &format!("tetra-{}", variant.key()), palette);
+        out.push('\n');
+    }
+    out.push_str(&format!("$tetra-colors: $tetra-{};\n", variants[0].0.key()));
+    out
+}
+
+fn color_map(palette: &ThemePalette) -> Map<String, Value> {
+    let mut colors = Map::new();
+    for slot in ColorSlot::ALL {
+        colors.insert(slot.key().to_string(), hex(&palette.get(slot)).into());
+    }
+    colors.insert("muted".to_string(), hex(&palette.muted()).into());
+    colors
+}
+
+fn tailwind_colors(variants: &[(Variant, ThemePalette)]) -> Value {
+    let mut colors = Map::new();
+    colors.insert("tetra".to_string(), color_map(&variants[0].1).into());
+    if variants.len() > 1 {
+        for (variant, palette) in variants {
+            colors.insert(format!("tetra-{}", variant.key()), color_map(palette).into());
+        }
+    }
+    json!({ "theme": { "extend": { "colors": colors } } })
+}
+
+pub fn tailwind_json(variants: &[(Variant, ThemePalette)]) -> String {
+    serde_json::to_string_pretty(&tailwind_colors(variants)).unwrap()
+}
+
+pub fn tailwind_js(variants: &[(Variant, ThemePalette)]) -> String {
+    format!("// Generated by Tetra, edits will be overwritten\nmodule.exports = {};\n", tailwind_json(variants))
+}
+
+/// fixed English, so the file doesn't change with the locale it was exported in
+fn description(slot: ColorSlot) -> &'static str {
+    match slot {
+        ColorSlot::Bg1 => "Primary background color",
+        ColorSlot::Bg2 => "Secondary background color",
+        ColorSlot::Bg3 => "Tertiary background color",
+        ColorSlot::Txt => "Text color",
+        ColorSlot::Red => "Red accent",
+        ColorSlot::Orange => "Orange accent",
+        ColorSlot::Yellow => "Yellow accent",
+        ColorSlot::Green => "Green accent",
+        ColorSlot::Blue => "Blue accent",
+        ColorSlot::Purple => "Purple accent",
+        ColorSlot::Pink => "Pink accent",
+    }
+}
+
+/// a W3C Design Tokens Community Group file, one group per variant
+pub fn design_tokens(variants: &[(Variant, ThemePalette)]) -> String {
+    let mut groups = Map::new();
+    for (variant, palette) in variants {
+        let mut group = Map::new();
+        group.insert("$type".to_string(), "color".into());
+        for slot in ColorSlot::ALL {
+            group.insert(slot.key().to_string(), json!({
+                "$value": hex(&palette.get(slot)),
+                "$description": description(slot),
+            }));
+        }
+        group.insert("muted".to_string(), json!({
+            "$value": hex(&palette.muted()),
+            "$description": "Text color dimmed towards the background",
+        }));
+        groups.insert(variant.key().to_string(), group.into());
+    }
+    let tokens = json!({ "tetra": groups });
+    serde_json::to_string_pretty(&tokens).unwrap()
+}
@@ -0,0 +1,189 @@
+use std::path::Path;
+use iced::Color;
+use gettextrs::gettext;
+use crate::color::{from_oklch, hue_distance};
+use crate::palette::{ColorSlot, ThemePalette, Variant};
+
+/// longest side images are scaled down to before clustering
+const SAMPLE_SIZE: u32 = 128;
+const CLUSTERS: usize = 12;
+const ITERATIONS: usize = 16;
+
+/// where each accent slot sits on the OKLCh hue wheel
+const ACCENT_HUES: [(ColorSlot, f32); 7] = [
+    (ColorSlot::Red, 25.0),
+    (ColorSlot::Orange, 55.0),
+    (ColorSlot::Yellow, 95.0),
+    (ColorSlot::Green, 145.0),
+    (ColorSlot::Blue, 255.0),
+    (ColorSlot::Purple, 300.0),
+    (ColorSlot::Pink, 345.0),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Cluster {
+    lab: [f32; 3],
+    weight: usize,
+}
+
+impl Cluster {
+    fn lightness(&self) -> f32 {
+        self.lab[0]
+    }
+    fn chroma(&self) -> f32 {
+        (self.lab[1] * self.lab[1] + self.lab[2] * self.lab[2]).sqrt()
+    }
+    fn hue(&self) -> f32 {
+        self.lab[2].atan2(self.lab[1]).to_degrees().rem_euclid(360.0)
+    }
+}
+
+/// a generated palette waiting for the user to pick it
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: String,
+    pub palette: ThemePalette,
+}
+
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn load_pixels(path: &Path) -> Result<Vec<[f32; 3]>, String> {
+    let image = image::open(path).map_err(|error| error.to_string())?;
+    let image = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgb8();
+    Ok(image.pixels().map(|pixel| {
+        let [r, g, b] = pixel.0;
+        crate::color::to_oklab(&Color::from_rgb8(r, g, b))
+    }).collect())
+}
+
+/// k-means in OKLab, seeded with farthest point picks so the result is deterministic
+fn k_means(pixels: &[[f32; 3]], k: usize) -> Vec<Cluster> {
+    let mut centers = vec![pixels[0]];
+    while centers.len() < k.min(pixels.len()) {
+        let farthest = pixels.iter().max_by(|a, b| {
+            let da = centers.iter().map(|center| distance(a, center)).fold(f32::MAX, f32::min);
+            let db = centers.iter().map(|center| distance(b, center)).fold(f32::MAX, f32::min);
+            da.total_cmp(&db)
+        });
+        match farthest {
+            Some(pixel) if !centers.contains(pixel) => centers.push(*pixel),
+            _ => break,
+        }
+    }
+    let mut weights = vec![0; centers.len()];
+    for _ in 0..ITERATIONS {
+        let mut sums = vec![[0.0f32; 3]; centers.len()];
+        weights = vec![0; centers.len()];
+        for pixel in pixels {
+            let nearest = (0..centers.len()).min_by(|a, b| distance(pixel, &centers[*a]).total_cmp(&distance(pixel, &centers[*b]))).unwrap();
+            for channel in 0..3 {
+                sums[nearest][channel] += pixel[channel];
+            }
+            weights[nearest] += 1;
+        }
+        for (i, center) in centers.iter_mut().enumerate() {
+            if weights[i] > 0 {
+                let n = weights[i] as f32;
+                *center = [sums[i][0] / n, sums[i][1] / n, sums[i][2] / n];
+            }
+        }
+    }
+    centers.into_iter().zip(weights).filter(|(_, weight)| *weight > 0).map(|(lab, weight)| Cluster { lab, weight }).collect()
+}
+
+/// `bg1..bg3` and `txt` from the darkest and lightest clusters, with the chroma tamed
+fn neutrals(clusters: &[Cluster], variant: Variant) -> (Color, Color, Color, Color) {
+    let mut by_lightness = clusters.to_vec();
+    by_lightness.sort_by(|a, b| a.lightness().total_cmp(&b.lightness()));
+    if variant == Variant::Light {
+        by_lightness.reverse();
+    }
+    // the heaviest cluster among the background half becomes the background
+    let half = by_lightness.len().div_ceil(2);
+    let background = by_lightness[..half].iter().max_by_key(|cluster| cluster.weight).copied().unwrap_or(by_lightness[0]);
+    let text = *by_lightness.last().unwrap();
+    let tame = |cluster: &Cluster, max_chroma: f32| {
+        let chroma = cluster.chroma().min(max_chroma);
+        [chroma, cluster.hue()]
+    };
+    let [bg_chroma, bg_hue] = tame(&background, 0.04);
+    let [txt_chroma, txt_hue] = tame(&text, 0.03);
+    let (bg_l, step, txt_l) = match variant {
+        Variant::Dark => (background.lightness().clamp(0.15, 0.25), 0.03, text.lightness().max(0.88)),
+        Variant::Light => (background.lightness().clamp(0.9, 0.97), -0.03, text.lightness().min(0.3)),
+    };
+    (
+        from_oklch([bg_l, bg_chroma, bg_hue]),
+        from_oklch([bg_l + step, bg_chroma, bg_hue]),
+        from_oklch([bg_l + step * 2.0, bg_chroma, bg_hue]),
+        from_oklch([txt_l, txt_chroma, txt_hue]),
+    )
+}
+
+/// the seven accents, each taking the hue of the nearest colorful cluster when one is close;
+/// `pull` is how far towards that cluster's hue the slot moves
+fn accents(clusters: &[Cluster], variant: Variant, pull: f32, palette: &mut ThemePalette) {
+    let lightness = match variant {
+        Variant::Dark => 0.78,
+        Variant::Light => 0.55,
+    };
+    let colorful: Vec<&Cluster> = clusters.iter().filter(|cluster| cluster.chroma() > 0.03).collect();
+    for (slot, target) in ACCENT_HUES {
+        let nearest = colorful.iter().min_by(|a, b| hue_distance(a.hue(), target).total_cmp(&hue_distance(b.hue(), target)));
+        let (hue, chroma) = match nearest {
+            Some(cluster) if hue_distance(cluster.hue(), target) < 40.0 => {
+                // capped so neighbouring slots can't collapse onto the same hue
+                let delta = ((cluster.hue() - target + 540.0).rem_euclid(360.0) - 180.0).clamp(-20.0, 20.0);
+                (target + delta * pull, cluster.chroma().clamp(0.09, 0.2))
+            }
+            _ => (target, 0.12),
+        };
+        palette.set(slot, from_oklch([lightness, chroma, hue.rem_euclid(360.0)]));
+    }
+}
+
+fn build(clusters: &[Cluster], variant: Variant, pull: f32, base: &ThemePalette) -> ThemePalette {
+    let mut palette = *base;
+    let (bg1, bg2, bg3, txt) = neutrals(clusters, variant);
+    palette.bg1 = bg1;
+    palette.bg2 = bg2;
+    palette.bg3 = bg3;
+    palette.txt = txt;
+    accents(clusters, variant, pull, &mut palette);
+    palette
+}
+
+/// dark and light palettes from an image, each in a faithful and a harmonized flavour;
+/// the variant matching the image's overall lightness comes first
+pub fn candidates(path: &Path, base: &ThemePalette) -> Result<Vec<Candidate>, String> {
+    let pixels = load_pixels(path)?;
+    if pixels.is_empty() {
+        return Err(gettext("The image has no pixels"));
+    }
+    let clusters = k_means(&pixels, CLUSTERS);
+    let total: usize = clusters.iter().map(|cluster| cluster.weight).sum();
+    let average = clusters.iter().map(|cluster| cluster.lightness() * cluster.weight as f32).sum::<f32>() / total as f32;
+    let variants = if average < 0.6 {
+        [Variant::Dark, Variant::Light]
+    } else {
+        [Variant::Light, Variant::Dark]
+    };
+    let mut out = vec![];
+    for variant in variants {
+        let label = match variant {
+            Variant::Dark => gettext("Dark"),
+            Variant::Light => gettext("Light"),
+        };
+        out.push(Candidate {
+            name: format!("{label}, {}", gettext("faithful")),
+            palette: build(&clusters, variant, 1.0, base),
+        });
+        out.push(Candidate {
+            name: format!("{label}, {}", gettext("harmonized")),
+            palette: build(&clusters, variant, 0.4, base),
+        });
+    }
+    Ok(out)
+}
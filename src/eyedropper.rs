@@ -0,0 +1,190 @@
+use std::cell::Cell;
+use std::path::Path;
+use iced::{mouse, Color, Point, Rectangle, Size};
+use iced::widget::canvas::{event, Cache, Event, Geometry, Program};
+use iced_style::Theme;
+use crate::Message;
+
+/// screen cells drawn across the canvas at most, so huge images stay cheap to draw
+const MAX_CELLS: f32 = 160.0;
+const MAX_ZOOM: f32 = 64.0;
+
+pub struct LoadedImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+    /// bumped on every load so cached drawings of an older image get thrown away
+    generation: u64,
+}
+
+impl LoadedImage {
+    pub fn open(path: &Path, generation: u64) -> Result<LoadedImage, String> {
+        let image = image::open(path).map_err(|error| error.to_string())?.to_rgb8();
+        Ok(LoadedImage {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.pixels().map(|pixel| pixel.0).collect(),
+            generation,
+        })
+    }
+    pub fn size(&self) -> (f32, f32) {
+        (self.width as f32, self.height as f32)
+    }
+    fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+    /// the average color of the `size` by `size` block centered on `(x, y)`
+    pub fn sample(&self, x: f32, y: f32, size: u32) -> Color {
+        let radius = (size.max(1) as i64 - 1) / 2;
+        let (cx, cy) = (x.floor() as i64, y.floor() as i64);
+        let mut sum = [0u64; 3];
+        let mut count = 0;
+        for py in cy - radius..=cy + radius {
+            for px in cx - radius..=cx + radius {
+                if px < 0 || py < 0 || px >= self.width as i64 || py >= self.height as i64 {
+                    continue;
+                }
+                let pixel = self.pixel(px as u32, py as u32);
+                for channel in 0..3 {
+                    sum[channel] += pixel[channel] as u64;
+                }
+                count += 1;
+            }
+        }
+        let count = count.max(1);
+        Color::from_rgb8((sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8)
+    }
+}
+
+/// which part of the image is on screen: a zoom on top of fit-to-canvas, around `center`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub zoom: f32,
+    pub center: (f32, f32),
+}
+
+impl Viewport {
+    pub fn fit(image: &LoadedImage) -> Viewport {
+        let (width, height) = image.size();
+        Viewport { zoom: 1.0, center: (width / 2.0, height / 2.0) }
+    }
+    fn scale(&self, image: &LoadedImage, bounds: Size) -> f32 {
+        let (width, height) = image.size();
+        (bounds.width / width).min(bounds.height / height) * self.zoom
+    }
+    /// image coordinates of the canvas' top left corner
+    fn origin(&self, image: &LoadedImage, bounds: Size) -> (f32, f32) {
+        let scale = self.scale(image, bounds);
+        (self.center.0 - bounds.width / scale / 2.0, self.center.1 - bounds.height / scale / 2.0)
+    }
+    fn image_point(&self, image: &LoadedImage, bounds: Size, point: Point) -> (f32, f32) {
+        let scale = self.scale(image, bounds);
+        let (x0, y0) = self.origin(image, bounds);
+        (x0 + point.x / scale, y0 + point.y / scale)
+    }
+    /// zooms by `factor` while keeping the image point under `point` where it is
+    fn zoomed(&self, image: &LoadedImage, bounds: Size, point: Point, factor: f32) -> Viewport {
+        let anchor = self.image_point(image, bounds, point);
+        let zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let next = Viewport { zoom, center: self.center };
+        let scale = next.scale(image, bounds);
+        let center = (
+            anchor.0 - (point.x - bounds.width / 2.0) / scale,
+            anchor.1 - (point.y - bounds.height / 2.0) / scale,
+        );
+        Viewport { zoom, center }.clamped(image, bounds)
+    }
+    /// keeps as much of the image on screen as the zoom allows
+    fn clamped(&self, image: &LoadedImage, bounds: Size) -> Viewport {
+        let (width, height) = image.size();
+        let scale = self.scale(image, bounds);
+        let (half_w, half_h) = (bounds.width / scale / 2.0, bounds.height / scale / 2.0);
+        let clamp = |value: f32, half: f32, size: f32| {
+            if half * 2.0 >= size {
+                size / 2.0
+            } else {
+                value.clamp(half, size - half)
+            }
+        };
+        Viewport { zoom: self.zoom, center: (clamp(self.center.0, half_w, width), clamp(self.center.1, half_h, height)) }
+    }
+}
+
+pub struct Eyedropper<'a> {
+    pub image: &'a LoadedImage,
+    pub viewport: Viewport,
+    pub sample_size: u32,
+}
+
+#[derive(Default)]
+pub struct EyedropperState {
+    cache: Cache,
+    drawn: Cell<Option<(u64, Viewport, Size)>>,
+}
+
+impl<'a> Program<Message> for Eyedropper<'a> {
+    type State = EyedropperState;
+
+    fn update(&self, _state: &mut Self::State, event: Event, bounds: Rectangle, cursor: mouse::Cursor) -> (event::Status, Option<Message>) {
+        let Some(point) = cursor.position_in(bounds) else {
+            return (event::Status::Ignored, None);
+        };
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let (x, y) = self.viewport.image_point(self.image, bounds.size(), point);
+                let (width, height) = self.image.size();
+                if x < 0.0 || y < 0.0 || x >= width || y >= height {
+                    return (event::Status::Captured, None);
+                }
+                (event::Status::Captured, Some(Message::SubmitColor(self.image.sample(x, y, self.sample_size))))
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / 40.0,
+                };
+                let viewport = self.viewport.zoomed(self.image, bounds.size(), point, 1.25f32.powf(lines));
+                (event::Status::Captured, Some(Message::EyedropperViewport(viewport)))
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, state: &Self::State, renderer: &iced::Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let key = (self.image.generation, self.viewport, bounds.size());
+        if state.drawn.get() != Some(key) {
+            state.cache.clear();
+            state.drawn.set(Some(key));
+        }
+        let geometry = state.cache.draw(renderer, bounds.size(), |frame| {
+            let size = bounds.size();
+            let scale = self.viewport.scale(self.image, size);
+            let (x0, y0) = self.viewport.origin(self.image, size);
+            let (width, height) = self.image.size();
+            // one cell per image pixel when zoomed in, coarser cells when the image is larger than the canvas
+            let cell = scale.max(size.width.max(size.height) / MAX_CELLS);
+            let mut sy = 0.0;
+            while sy < size.height {
+                let mut sx = 0.0;
+                while sx < size.width {
+                    let (ix, iy) = (x0 + (sx + cell / 2.0) / scale, y0 + (sy + cell / 2.0) / scale);
+                    if ix >= 0.0 && iy >= 0.0 && ix < width && iy < height {
+                        let [r, g, b] = self.image.pixel(ix as u32, iy as u32);
+                        frame.fill_rectangle(Point::new(sx, sy), Size::new(cell, cell), Color::from_rgb8(r, g, b));
+                    }
+                    sx += cell;
+                }
+                sy += cell;
+            }
+        });
+        vec![geometry]
+    }
+
+    fn mouse_interaction(&self, _state: &Self::State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
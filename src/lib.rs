@@ -0,0 +1,2 @@
+/// palette updates pushed from a running editor, for other Oceania apps to subscribe to
+pub mod live;
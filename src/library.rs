@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+use gettextrs::gettext;
+use oceania_style::get_home;
+use crate::palette::ThemePalette;
+use crate::theme::{self, Theme, ThemeMeta};
+
+/// a named palette kept in `~/Oceania/themes`
+#[derive(Debug, Clone)]
+pub struct LibraryTheme {
+    pub name: String,
+    pub palette: ThemePalette,
+    pub meta: ThemeMeta,
+    pub extends: Option<String>,
+}
+
+/// `~/Oceania`: the live `theme.toml` and the named themes in `themes/` that it can extend
+#[derive(Debug, Clone, PartialEq)]
+pub struct Library {
+    root: PathBuf,
+}
+
+impl Library {
+    pub fn home() -> Library {
+        Library::at(PathBuf::from(get_home()).join("Oceania"))
+    }
+    /// a library kept somewhere else, so tests don't touch the real one
+    pub fn at(root: PathBuf) -> Library {
+        Library { root }
+    }
+    pub fn dir(&self) -> PathBuf {
+        self.root.join("themes")
+    }
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir().join(format!("{name}.toml"))
+    }
+    /// the live theme every Oceania app reads
+    pub fn theme_path(&self) -> String {
+        self.root.join("theme.toml").to_string_lossy().into_owned()
+    }
+    /// one of Tetra's own settings files, kept in `tetra/`
+    pub fn settings_path(&self, file: &str) -> PathBuf {
+        self.root.join("tetra").join(file)
+    }
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).exists()
+    }
+    /// every readable theme in the library, sorted by name
+    pub fn list(&self) -> Vec<LibraryTheme> {
+        let Ok(entries) = std::fs::read_dir(self.dir()) else {
+            return vec![];
+        };
+        let mut out: Vec<LibraryTheme> = entries.filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            let theme = self.load(&name).ok()?;
+            Some(LibraryTheme { name, palette: theme.palette, meta: theme.meta, extends: theme.extends })
+        }).collect();
+        out.sort_by_key(|theme| theme.name.to_lowercase());
+        out
+    }
+    pub fn load(&self, name: &str) -> Result<Theme, String> {
+        theme::load(&self.path(name).to_string_lossy(), self)
+    }
+    /// `name` followed by the chain of themes it extends
+    pub fn ancestors(&self, name: &str) -> Vec<String> {
+        let mut out: Vec<String> = vec![];
+        let mut current = Some(name.to_string());
+        while let Some(name) = current {
+            if out.contains(&name) {
+                break;
+            }
+            current = self.load(&name).ok().and_then(|theme| theme.extends);
+            out.push(name);
+        }
+        out
+    }
+    /// themes that directly extend `name`
+    fn children(&self, name: &str) -> Vec<(String, Theme)> {
+        self.list().into_iter().filter(|entry| entry.extends.as_deref() == Some(name)).map(|entry| {
+            (entry.name, Theme { palette: entry.palette, meta: entry.meta, extends: entry.extends })
+        }).collect()
+    }
+    /// `base`, or `base 2`, `base 3`... whichever isn't taken yet
+    pub fn unique_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut n = 2;
+        while self.exists(&name) {
+            name = format!("{base} {n}");
+            n += 1;
+        }
+        name
+    }
+    /// derived themes only keep the colors that differ from their base
+    pub fn save(&self, name: &str, theme: &Theme) -> Result<(), String> {
+        validate(name)?;
+        let contents = match &theme.extends {
+            Some(extends) => theme::to_string_derived(theme, extends, &self.load(extends)?.palette),
+            None => theme::to_string(theme)
+        };
+        std::fs::create_dir_all(self.dir()).map_err(|error| error.to_string())?;
+        std::fs::write(self.path(name), contents).map_err(|error| error.to_string())
+    }
+    /// stores `palette` under a new name, returning the name actually used
+    pub fn create(&self, name: &str, theme: &Theme) -> Result<String, String> {
+        validate(name)?;
+        let name = self.unique_name(name.trim());
+        self.save(&name, theme)?;
+        Ok(name)
+    }
+    pub fn duplicate(&self, name: &str) -> Result<String, String> {
+        let theme = self.load(name)?;
+        self.create(&format!("{name} {}", gettext("copy")), &theme)
+    }
+    pub fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        let to = to.trim();
+        validate(to)?;
+        if from == to {
+            return Ok(());
+        }
+        if self.exists(to) {
+            return Err(gettext("A theme with that name already exists"));
+        }
+        let children = self.children(from);
+        std::fs::rename(self.path(from), self.path(to)).map_err(|error| error.to_string())?;
+        for (child, mut theme) in children {
+            theme.extends = Some(to.to_string());
+            self.save(&child, &theme)?;
+        }
+        self.repoint_live(from, Some(to))
+    }
+    /// themes extending `name` keep their current colors but stop inheriting
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        for (child, mut theme) in self.children(name) {
+            theme.extends = None;
+            self.save(&child, &theme)?;
+        }
+        std::fs::remove_file(self.path(name)).map_err(|error| error.to_string())?;
+        self.repoint_live(name, None)
+    }
+    /// makes the live theme extend `to` instead of `from`, or nothing. a `theme.toml` that can't be
+    /// rewritten safely is left as it is; it holds every color, so it still reads without its base.
+    fn repoint_live(&self, from: &str, to: Option<&str>) -> Result<(), String> {
+        let path = self.theme_path();
+        let Ok(mut theme) = theme::load(&path, self) else {
+            return Ok(());
+        };
+        if theme.extends.as_deref() != Some(from) || theme::overwrite_risk(&path, self).is_some() {
+            return Ok(());
+        }
+        theme.extends = to.map(str::to_string);
+        theme::write(&path, &theme).map_err(|error| error.to_string())
+    }
+}
+
+pub fn validate(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err(gettext("Theme names can't be empty"));
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(gettext("Theme names can't contain slashes or start with a dot"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::presets;
+
+    /// an empty library in a scratch directory of its own, so tests can run side by side
+    pub fn scratch(name: &str) -> Library {
+        let root = std::env::temp_dir().join(format!("tetra-test-{}", std::process::id())).join(name);
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("themes")).unwrap();
+        Library::at(root)
+    }
+
+    /// the first preset without metadata or a base
+    pub fn sample() -> Theme {
+        Theme { palette: presets::PRESETS[0].palette(), meta: ThemeMeta::default(), extends: None }
+    }
+
+    fn extending(base: &str) -> Theme {
+        Theme { extends: Some(base.to_string()), ..sample() }
+    }
+
+    #[test]
+    fn inheritance_loops_are_errors() {
+        let library = scratch("loops");
+        std::fs::write(library.path("a"), "schema_version = 2\nextends = \"b\"\n").unwrap();
+        std::fs::write(library.path("b"), "schema_version = 2\nextends = \"a\"\n").unwrap();
+        let error = library.load("a").unwrap_err();
+        assert!(error.contains("b → a → b"), "{error}");
+    }
+
+    #[test]
+    fn bases_outside_the_library_are_refused() {
+        let library = scratch("escape");
+        std::fs::write(library.path("escape"), "schema_version = 2\nextends = \"../../.ssh/x\"\n").unwrap();
+        assert_eq!(library.load("escape").unwrap_err(), validate("../../.ssh/x").unwrap_err());
+    }
+
+    #[test]
+    fn rename_repoints_children() {
+        let library = scratch("rename");
+        library.save("base", &sample()).unwrap();
+        let mut child = extending("base");
+        child.palette.red = iced::Color::from_rgb8(0xff, 0, 0);
+        library.save("child", &child).unwrap();
+        library.rename("base", "renamed").unwrap();
+        assert!(!library.exists("base"));
+        let loaded = library.load("child").unwrap();
+        assert_eq!(loaded.extends.as_deref(), Some("renamed"));
+        assert_eq!(loaded.palette, child.palette);
+    }
+
+    #[test]
+    fn delete_detaches_children() {
+        let library = scratch("delete");
+        library.save("base", &sample()).unwrap();
+        library.save("child", &extending("base")).unwrap();
+        library.delete("base").unwrap();
+        assert!(!library.exists("base"));
+        let loaded = library.load("child").unwrap();
+        assert_eq!(loaded.extends, None);
+        assert_eq!(loaded.palette, sample().palette);
+    }
+
+    #[test]
+    fn the_live_theme_follows_its_base() {
+        let library = scratch("live");
+        library.save("base", &sample()).unwrap();
+        theme::write(&library.theme_path(), &extending("base")).unwrap();
+        library.rename("base", "renamed").unwrap();
+        assert_eq!(theme::load(&library.theme_path(), &library).unwrap().extends.as_deref(), Some("renamed"));
+        library.delete("renamed").unwrap();
+        assert_eq!(theme::load(&library.theme_path(), &library).unwrap(), sample());
+    }
+
+    #[test]
+    fn complete_themes_read_without_their_base() {
+        let library = scratch("orphan");
+        theme::write(&library.theme_path(), &extending("gone")).unwrap();
+        assert_eq!(theme::load(&library.theme_path(), &library).unwrap().palette, sample().palette);
+        std::fs::write(library.path("partial"), "schema_version = 2\nextends = \"gone\"\nred = \"ff0000\"\n").unwrap();
+        assert!(library.load("partial").is_err());
+    }
+}
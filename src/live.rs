@@ -0,0 +1,120 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use oceania_style::ThemeFile;
+use serde_derive::{Serialize, Deserialize};
+
+/// one JSON line on the socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Update {
+    /// false while the palette is only being previewed in the editor
+    pub saved: bool,
+    /// the same hex strings `theme.toml` holds
+    pub colors: ThemeFile,
+}
+
+/// `$XDG_RUNTIME_DIR/tetra.sock`, or a per-user socket in the temp directory
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("tetra.sock"),
+        None => std::env::temp_dir().join(format!("tetra-{}.sock", whoami::username())),
+    }
+}
+
+/// connects to the running editor, which sends the current palette right away and then every change,
+/// saved or only previewed. the iterator ends when the editor quits.
+///
+/// ```no_run
+/// for update in tetra::live::subscribe()? {
+///     let bg = oceania_style::string_to_color(update.colors.bg_color1);
+///     // restyle with `bg`...
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn subscribe() -> io::Result<Updates> {
+    Ok(Updates { lines: BufReader::new(UnixStream::connect(socket_path())?).lines() })
+}
+
+pub struct Updates {
+    lines: io::Lines<BufReader<UnixStream>>,
+}
+
+impl Iterator for Updates {
+    type Item = Update;
+    fn next(&mut self) -> Option<Update> {
+        // lines a newer editor added fields to still parse; anything else is skipped
+        self.lines.by_ref().map_while(Result::ok).find_map(|line| serde_json::from_str(&line).ok())
+    }
+}
+
+#[derive(Default)]
+struct Clients {
+    streams: Vec<UnixStream>,
+    last: Option<String>,
+}
+
+/// writes the whole line without waiting, or reports the client as stuck. a client that gets
+/// part of a line can't make sense of the rest, so it's dropped either way.
+fn deliver(stream: &mut UnixStream, line: &str) -> bool {
+    matches!(stream.write(line.as_bytes()), Ok(written) if written == line.len())
+}
+
+/// the editor's end of the socket. clients are written to from a thread of their own, so one
+/// that stops reading can't hold up the editor.
+pub struct Broadcaster {
+    lines: Sender<String>,
+}
+
+impl Broadcaster {
+    /// fails if another Tetra is already broadcasting
+    pub fn start() -> io::Result<Broadcaster> {
+        let path = socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::from(io::ErrorKind::AddrInUse));
+        }
+        // left over from an editor that didn't shut down cleanly
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let clients = Arc::new(Mutex::new(Clients::default()));
+        let accepting = clients.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                if stream.set_nonblocking(true).is_err() {
+                    continue;
+                }
+                let mut clients = accepting.lock().unwrap();
+                if let Some(line) = &clients.last {
+                    if !deliver(&mut stream, line) {
+                        continue;
+                    }
+                }
+                clients.streams.push(stream);
+            }
+        });
+        let (lines, received) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            while let Ok(line) = received.recv() {
+                // only the newest palette matters, so whatever piled up during a drag is skipped
+                let line = received.try_iter().last().unwrap_or(line);
+                let mut clients = clients.lock().unwrap();
+                clients.streams.retain_mut(|stream| deliver(stream, &line));
+                clients.last = Some(line);
+            }
+        });
+        Ok(Broadcaster { lines })
+    }
+    pub fn send(&self, update: &Update) {
+        // ThemeFile is plain strings, so this can't fail
+        let line = serde_json::to_string(update).unwrap() + "\n";
+        // the writer only stops once this is dropped
+        let _ = self.lines.send(line);
+    }
+}
+
+impl Drop for Broadcaster {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(socket_path());
+    }
+}
@@ -1,45 +1,197 @@
 #![deny(unsafe_code)]
-use iced::{Result, Application, Color, Settings, Length};
-use iced::widget::{Button, Column, Row, Container, Text, Space};
+use iced::{Result, Application, Background, Color, Settings, Length};
+use iced::widget::{container, responsive, Button, Canvas, Checkbox, Column, Row, Container, Text, Space, PickList, Scrollable, Slider, TextInput};
 use iced_aw::ColorPicker;
 use iced_style::Theme;
 use gettextrs::*;
 use iced_style::theme::Palette;
-use oceania_style::{ButtonStyle, get_home, get_set_theme, ListStyle, make_custom_theme, MenuStyle, mk_app_theme, SelectedTheme, string_to_color, ThemeCustom, ThemeFile, ThemeSet};
+use oceania_style::{ButtonStyle, get_set_theme, ListStyle, make_custom_theme, MenuStyle, mk_app_theme, SelectedTheme, ThemeCustom, ThemeSet};
 use serde_derive::{Serialize, Deserialize};
-use toml;
+use std::path::{Path, PathBuf};
+use crate::export::{export, ExportTarget, SyntaxMapping};
+use crate::extract::Candidate;
+use crate::eyedropper::{Eyedropper, LoadedImage, Viewport};
+use crate::bundle::{Bundle, Conflict};
+use crate::compare::CompareSource;
+use crate::library::{Library, LibraryTheme};
+use crate::palette::{ColorSlot, SlotGroup, ThemePalette, Variant};
+use crate::theme::{theme_path, ThemeMeta, SCHEMA_VERSION};
+use crate::swatch::SwatchFormat;
+use tetra::live::{Broadcaster, Update};
 
+mod bundle;
+mod cli;
+mod code;
+mod color;
+mod compare;
+mod export;
+mod extract;
+mod eyedropper;
+mod library;
+mod palette;
+mod portal;
+mod presets;
+mod schedule;
+mod swatch;
+mod theme;
+mod wal;
+mod watch;
 
 
-pub fn col_from_str(string: &str) -> Color {
-    string_to_color(string.to_string())
-}
 fn main() -> Result {
     let _ = textdomain("TetraTheme");
     let _ = bind_textdomain_codeset("TetraTheme", "UTF-8");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
     Configurator::run(Settings::default())
 }
 const COLOR_SIZE: u16 = 50;
+/// editor width from which the preview sits beside the slots instead of above them
+const WIDE_LAYOUT: f32 = 1000.0;
 
 fn button_style_from_col(color: &Color) -> ButtonStyle{
     ButtonStyle { border_radius: 2.5, txt_color: color.clone(), bg_color: color.clone(), border_color: color.clone(), border_width: 0.0, shadow_offset: iced::Vector { x: 0.0, y: 0.0 } }
 }
 
+struct SwatchStyle(Color);
+
+impl container::StyleSheet for SwatchStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Background::Color(self.0)),
+            border_radius: 2.5.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// a non interactive square of `color`
+fn swatch<'a>(color: Color, size: u16) -> Container<'a, Message> {
+    Container::new(Space::new(size, size)).style(iced::theme::Container::Custom(Box::new(SwatchStyle(color))))
+}
+
+/// all eleven slots of `palette` side by side, for previews
+fn swatch_strip<'a>(palette: &ThemePalette, size: u16) -> Row<'a, Message> {
+    ColorSlot::ALL.iter().fold(Row::new().spacing(2), |row, slot| row.push(swatch(palette.get(*slot), size)))
+}
+
+/// the outline around the selected slot
+struct FocusStyle;
+
+impl container::StyleSheet for FocusStyle {
+    type Style = Theme;
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            border_radius: 4.0.into(),
+            border_width: 2.0,
+            border_color: style.palette().primary,
+            ..Default::default()
+        }
+    }
+}
+
+/// how many edits Undo can step back through
+const UNDO_LIMIT: usize = 100;
+
+/// keyboard control of the editor, spelled out under the tools. keys a focused text field uses are left to it,
+/// apart from Escape.
+fn shortcut(event: iced::Event, status: iced::event::Status) -> Option<Message> {
+    use iced::keyboard::KeyCode;
+    let iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key_code, modifiers }) = event else {
+        return None;
+    };
+    if key_code == KeyCode::Escape {
+        return Some(Message::ClosePicker);
+    }
+    if status == iced::event::Status::Captured {
+        return None;
+    }
+    let step = if modifiers.shift() { 5.0 } else { 1.0 };
+    match key_code {
+        KeyCode::C if modifiers.command() => Some(Message::CopySelected),
+        KeyCode::V if modifiers.command() => Some(Message::PasteSelected),
+        KeyCode::S if modifiers.command() => Some(Message::Save),
+        KeyCode::Z if modifiers.command() && modifiers.shift() => Some(Message::Redo),
+        KeyCode::Z if modifiers.command() => Some(Message::Undo),
+        KeyCode::Y if modifiers.command() => Some(Message::Redo),
+        KeyCode::Up => Some(Message::MoveSelection(-1)),
+        KeyCode::Down => Some(Message::MoveSelection(1)),
+        KeyCode::Enter | KeyCode::NumpadEnter => Some(Message::OpenSelected),
+        KeyCode::Left if modifiers.alt() => Some(Message::Nudge(0.0, -2.0 * step)),
+        KeyCode::Right if modifiers.alt() => Some(Message::Nudge(0.0, 2.0 * step)),
+        KeyCode::Left => Some(Message::Nudge(-0.01 * step, 0.0)),
+        KeyCode::Right => Some(Message::Nudge(0.01 * step, 0.0)),
+        _ => None
+    }
+}
+
 struct Configurator {
-    bg1: Color,
-    bg2: Color,
-    bg3: Color,
-    txt: Color,
-    red: Color,
-    orange: Color,
-    yellow: Color,
-    green: Color,
-    blue: Color,
-    purple: Color,
-    pink: Color,
+    palette: ThemePalette,
+    meta: ThemeMeta,
+    meta_tags: String,
+    legacy_format: bool,
+    extends: Option<String>,
+    base: Option<ThemePalette>,
     open_picker: Option<ColorSlot>,
+    /// the palette from before the slider drag in progress, so the whole drag undoes as one step
+    drag_start: Option<ThemePalette>,
     theme_set: ThemeSet,
     theme_type: SelectedTheme,
+    export_target: ExportTarget,
+    swatch_format: SwatchFormat,
+    swatch_path: String,
+    terminal_path: String,
+    wallpaper_path: String,
+    candidates: Vec<Candidate>,
+    eyedropper_path: String,
+    eyedropper_image: Option<LoadedImage>,
+    eyedropper_viewport: Viewport,
+    /// slot that eyedropper samples and the copy/paste shortcuts use while no picker is open
+    sample_slot: ColorSlot,
+    /// text typed into a slot's color field, kept while it doesn't parse
+    slot_text: Option<(ColorSlot, String)>,
+    collapsed: Vec<SlotGroup>,
+    undo: Vec<ThemePalette>,
+    redo: Vec<ThemePalette>,
+    /// for the semantic group
+    syntax: SyntaxMapping,
+    sample_size: u32,
+    images_loaded: u64,
+    library: Vec<LibraryTheme>,
+    library_selected: Option<String>,
+    library_name: String,
+    confirm_delete: bool,
+    show_presets: bool,
+    show_compare: bool,
+    compare_left: CompareSource,
+    compare_right: CompareSource,
+    /// palettes loaded for the compare sources, `None` for the editor or on errors
+    compare_loaded: (Option<ThemePalette>, Option<ThemePalette>),
+    compare_path: String,
+    show_blend: bool,
+    blend_from: CompareSource,
+    blend_to: CompareSource,
+    blend_loaded: (Option<ThemePalette>, Option<ThemePalette>),
+    blend_amount: f32,
+    bundle_path: String,
+    /// a bundle whose name is already taken, waiting for the user to decide
+    pending_bundle: Option<Bundle>,
+    /// `None` when another Tetra is already broadcasting
+    live: Option<Broadcaster>,
+    /// `theme.toml` as the editor last read or wrote it, the base for telling local edits from outside ones
+    disk: Option<theme::Theme>,
+    /// the exact text last written to `theme.toml`, so the watcher can skip our own saves
+    written: Option<String>,
+    /// an outside change to `theme.toml` that arrived while there were unsaved edits
+    external: Option<theme::Theme>,
+    /// why Save would lose what `theme.toml` holds, waiting for the user to confirm
+    overwrite: Option<String>,
+    /// the library theme Activate is waiting to write, when the confirmation is for it rather than Save
+    activating: Option<String>,
+    status: String,
 }
 #[derive(Serialize, Deserialize)]
 struct CuttlefishCfg {
@@ -142,77 +294,169 @@ fn generate_theme(theme_slot: SelectedTheme) -> Option<ThemeCustom> {
     }
 }
 
-fn format_radix(mut x: u32, radix: u32) -> String {
-    let mut result = vec![];
-
-    loop {
-        let m = x % radix;
-        x = x / radix;
-
-        // will panic if you use a bad radix (< 2 or > 36).
-        result.push(std::char::from_digit(m, radix).unwrap());
-        if x == 0 {
-            break;
-        }
-    }
-    result.into_iter().rev().collect()
-}
-fn string_from_col(color: &Color) -> String {
-    let rgba = color.into_rgba8();
-    let red_str = format_radix(rgba[0].into(), 16);
-    let green_str = format_radix(rgba[1].into(), 16);
-    let blue_str = format_radix(rgba[2].into(), 16);
-    format!("{red_str}{green_str}{blue_str}")
+/// bare `rrggbb`, zero padded so every Oceania app reads it back the same way
+pub fn string_from_col(color: &Color) -> String {
+    color::hex_bare(color)
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     OpenPicker(ColorSlot),
     SubmitColor(Color),
+    Drag(ColorSlot, [f32; 3]),
+    DragEnd,
     ClosePicker,
-    Save
+    Save,
+    SaveVariant,
+    SelectExport(ExportTarget),
+    Export,
+    SelectSwatchFormat(SwatchFormat),
+    SwatchPath(String),
+    ImportSwatches,
+    ExportSwatches,
+    TerminalPath(String),
+    ImportTerminalColors,
+    WallpaperPath(String),
+    ExtractPalette,
+    ApplyCandidate(usize),
+    DiscardCandidates,
+    EyedropperPath(String),
+    LoadEyedropperImage,
+    CloseEyedropper,
+    EyedropperViewport(Viewport),
+    ResetEyedropperViewport,
+    SelectSampleSlot(ColorSlot),
+    SelectSampleSize(u32),
+    LibraryName(String),
+    SelectTheme(String),
+    NewTheme,
+    DuplicateTheme,
+    RenameTheme,
+    DeleteTheme,
+    ConfirmDeleteTheme,
+    StoreTheme,
+    ActivateTheme,
+    TogglePresets,
+    LoadPreset(usize),
+    MetaName(String),
+    MetaAuthor(String),
+    MetaVersion(String),
+    MetaVariant(Variant),
+    MetaLicense(String),
+    MetaDescription(String),
+    MetaTags(String),
+    LegacyFormat(bool),
+    SelectBase(String),
+    DetachBase,
+    InheritSlot(ColorSlot),
+    BundlePath(String),
+    ExportBundle,
+    ImportBundle,
+    InstallBundle(Conflict),
+    CancelBundle,
+    CopyThemeCode,
+    PasteThemeCode,
+    PastedThemeCode(Option<String>),
+    ToggleCompare,
+    CompareLeft(CompareSource),
+    CompareRight(CompareSource),
+    ComparePath(String),
+    CompareFile,
+    ToggleBlend,
+    BlendFrom(CompareSource),
+    BlendTo(CompareSource),
+    BlendAmount(f32),
+    UseBlend,
+    SaveBlend,
+    ToggleGroup(SlotGroup),
+    /// steps the selected slot through `ColorSlot::ALL`
+    MoveSelection(i32),
+    OpenSelected,
+    /// OKLCh lightness and hue (in degrees) added to the selected slot
+    Nudge(f32, f32),
+    Undo,
+    Redo,
+    SlotText(ColorSlot, String),
+    CommitSlotText,
+    CopySlot(ColorSlot),
+    PasteSlot(ColorSlot),
+    PastedSlot(ColorSlot, Option<String>),
+    CopySelected,
+    PasteSelected,
+    ThemeFileChanged,
+    UseExternal,
+    KeepLocal,
+    MergeExternal,
+    ConfirmOverwrite,
+    CancelOverwrite,
 }
-#[derive(Debug, Clone, PartialEq)]
-enum ColorSlot {
-    Bg1,
-    Bg2,
-    Bg3,
-    Txt,
-    Red,
-    Orange,
-    Yellow,
-    Green,
-    Blue,
-    Purple,
-    Pink
+
+/// a `theme.toml` that can't be read or came from a newer Tetra is reported rather than silently replaced
+fn startup_status() -> String {
+    theme::overwrite_risk(&theme_path(), &Library::home()).map(|risk| format!("{} {risk}", gettext("theme.toml won't be overwritten without asking:"))).unwrap_or_default()
+}
+
+/// what the editor starts with when there's no readable `theme.toml`
+fn fallback_document() -> theme::Theme {
+    theme::Theme { palette: presets::CATPPUCCIN_MACCHIATO.palette(), meta: ThemeMeta::default(), extends: None }
 }
 
 impl Default for Configurator {
     fn default() -> Self {
-        let path = format!("{}/Oceania/theme.toml", get_home());
-        let theme_file: Option<ThemeFile> = match std::fs::read_to_string(path) {
-            Ok(value) => {
-                Some(toml::from_str(&value).unwrap())
-            }
-            Err(..) => {
-                None
-            }
-        };
-        match theme_file {
-            Some(value) => {
+        match theme::load(&theme_path(), &Library::home()).ok() {
+            Some(theme) => {
+                let base = theme.base(&Library::home());
+                let disk = Some(theme.clone());
                 Configurator {
-                    bg1: string_to_color(value.bg_color1.clone()),
-                    bg2: string_to_color(value.bg_color2.clone()),
-                    bg3: string_to_color(value.bg_color3.clone()),
-                    txt: string_to_color(value.txt_color.clone()),
-                    red: string_to_color(value.red.clone()),
-                    orange: string_to_color(value.orange),
-                    yellow: string_to_color(value.yellow),
-                    green: string_to_color(value.green.clone()),
-                    blue: string_to_color(value.blue.clone()),
-                    purple: string_to_color(value.purple),
-                    pink: string_to_color(value.pink),
+                    palette: theme.palette,
+                    meta_tags: theme.meta.tags_text(),
+                    legacy_format: false,
+                    meta: theme.meta,
+                    extends: theme.extends,
+                    base,
                     open_picker: None,
+                    drag_start: None,
+                    export_target: ExportTarget::Neovim,
+                    swatch_format: SwatchFormat::Gpl,
+                    swatch_path: String::new(),
+                    terminal_path: String::new(),
+                    wallpaper_path: String::new(),
+                    candidates: vec![],
+                    eyedropper_path: String::new(),
+                    eyedropper_image: None,
+                    eyedropper_viewport: Viewport { zoom: 1.0, center: (0.0, 0.0) },
+                    sample_slot: ColorSlot::Bg1,
+                    sample_size: 1,
+                    slot_text: None,
+                    collapsed: vec![SlotGroup::Ansi, SlotGroup::Semantic],
+                    undo: vec![],
+                    redo: vec![],
+                    syntax: SyntaxMapping::load(),
+                    images_loaded: 0,
+                    library: Library::home().list(),
+                    library_selected: None,
+                    library_name: String::new(),
+                    confirm_delete: false,
+                    show_presets: false,
+                    show_compare: false,
+                    compare_left: CompareSource::Saved,
+                    compare_right: CompareSource::Current,
+                    compare_loaded: (None, None),
+                    compare_path: String::new(),
+                    show_blend: false,
+                    blend_from: CompareSource::Variant(Variant::Light),
+                    blend_to: CompareSource::Variant(Variant::Dark),
+                    blend_loaded: (None, None),
+                    blend_amount: 0.5,
+                    bundle_path: String::new(),
+                    pending_bundle: None,
+                    live: Broadcaster::start().ok(),
+                    disk,
+                    written: None,
+                    external: None,
+                    overwrite: None,
+                    activating: None,
+                    status: startup_status(),
                     theme_type: get_set_theme(),
                     theme_set: ThemeSet {
                         light: ThemeCustom {
@@ -305,18 +549,55 @@ impl Default for Configurator {
             }
             None => {
                 Configurator { 
-                    bg1: col_from_str("181926"), 
-                    bg2: col_from_str("1e2030"), 
-                    bg3: col_from_str("24273a"), 
-                    txt: col_from_str("cad3f5"),
-                    red: col_from_str("ed8796"), 
-                    orange: col_from_str("f5a97f"), 
-                    yellow: col_from_str("eed49f"), 
-                    green: col_from_str("a6da95"),
-                    blue: col_from_str("8aadf4"), 
-                    purple: col_from_str("c6a0f6"),
-                    pink: col_from_str("f5bde6"), 
+                    palette: fallback_document().palette,
+                    meta: fallback_document().meta,
+                    meta_tags: String::new(),
+                    legacy_format: false,
+                    extends: None,
+                    base: None,
                     open_picker: None,
+                    drag_start: None,
+                    export_target: ExportTarget::Neovim,
+                    swatch_format: SwatchFormat::Gpl,
+                    swatch_path: String::new(),
+                    terminal_path: String::new(),
+                    wallpaper_path: String::new(),
+                    candidates: vec![],
+                    eyedropper_path: String::new(),
+                    eyedropper_image: None,
+                    eyedropper_viewport: Viewport { zoom: 1.0, center: (0.0, 0.0) },
+                    sample_slot: ColorSlot::Bg1,
+                    sample_size: 1,
+                    slot_text: None,
+                    collapsed: vec![SlotGroup::Ansi, SlotGroup::Semantic],
+                    undo: vec![],
+                    redo: vec![],
+                    syntax: SyntaxMapping::load(),
+                    images_loaded: 0,
+                    library: Library::home().list(),
+                    library_selected: None,
+                    library_name: String::new(),
+                    confirm_delete: false,
+                    show_presets: false,
+                    show_compare: false,
+                    compare_left: CompareSource::Saved,
+                    compare_right: CompareSource::Current,
+                    compare_loaded: (None, None),
+                    compare_path: String::new(),
+                    show_blend: false,
+                    blend_from: CompareSource::Variant(Variant::Light),
+                    blend_to: CompareSource::Variant(Variant::Dark),
+                    blend_loaded: (None, None),
+                    blend_amount: 0.5,
+                    bundle_path: String::new(),
+                    pending_bundle: None,
+                    live: Broadcaster::start().ok(),
+                    disk: None,
+                    written: None,
+                    external: None,
+                    overwrite: None,
+                    activating: None,
+                    status: startup_status(),
                     theme_type: get_set_theme(),
                     theme_set: ThemeSet { 
                         light: generate_theme(SelectedTheme::Light).unwrap(),
@@ -329,6 +610,416 @@ impl Default for Configurator {
     }
 }
 
+impl Configurator {
+    fn install_bundle(&mut self, bundle: Bundle, conflict: Conflict) {
+        match bundle::install(&bundle, conflict) {
+            Ok(name) => {
+                self.load_document(bundle.theme);
+                self.library_result(Ok(name.clone()));
+                self.status = format!("{} {name}", gettext("Imported"));
+            }
+            Err(error) => self.status = format!("{} {error}", gettext("Import failed:"))
+        }
+    }
+    /// loads a compare or blend source, leaving the editor's palette to be read at view time
+    fn load_source(&mut self, source: &CompareSource) -> Option<ThemePalette> {
+        match source {
+            CompareSource::Current => None,
+            source => source.load().map_err(|error| self.status = error).ok()
+        }
+    }
+    fn reload_compare(&mut self) {
+        self.compare_loaded = (self.load_source(&self.compare_left.clone()), self.load_source(&self.compare_right.clone()));
+    }
+    fn reload_blend(&mut self) {
+        self.blend_loaded = (self.load_source(&self.blend_from.clone()), self.load_source(&self.blend_to.clone()));
+    }
+    fn blended(&self) -> Option<ThemePalette> {
+        let from = self.compare_palette(&self.blend_from, self.blend_loaded.0)?;
+        let to = self.compare_palette(&self.blend_to, self.blend_loaded.1)?;
+        Some(from.blend(&to, self.blend_amount))
+    }
+    fn compare_palette(&self, source: &CompareSource, loaded: Option<ThemePalette>) -> Option<ThemePalette> {
+        match source {
+            CompareSource::Current => Some(self.palette),
+            _ => loaded
+        }
+    }
+    fn document(&self) -> theme::Theme {
+        theme::Theme { palette: self.palette, meta: self.meta.clone(), extends: self.extends.clone() }
+    }
+    fn load_document(&mut self, theme: theme::Theme) {
+        self.base = theme.base(&Library::home());
+        self.palette = theme.palette;
+        self.meta_tags = theme.meta.tags_text();
+        self.meta = theme.meta;
+        self.extends = theme.extends;
+    }
+    /// the slot's name, marked as inherited or with a way back to the base color
+    fn slot_label(&self, slot: ColorSlot) -> Row<'_, Message> {
+        let label = Row::new().push(Text::new(slot.label())).align_items(iced::Alignment::Center).spacing(10);
+        match &self.base {
+            Some(base) if base.get(slot) == self.palette.get(slot) => label.push(Text::new(gettext("inherited")).size(12)),
+            Some(_) => label.push(Button::new(Text::new(gettext("Inherit")).size(12)).on_press(Message::InheritSlot(slot))),
+            None => label
+        }
+    }
+    /// a field for typing or pasting the slot's color in any notation `color::parse` reads
+    fn slot_entry(&self, slot: ColorSlot) -> Row<'_, Message> {
+        let current = self.palette.get(slot);
+        let text = match &self.slot_text {
+            Some((typing, text)) if *typing == slot && !color::parse(text).is_some_and(|color| color != current) => text.clone(),
+            _ => color::hex(&current),
+        };
+        let feedback = if color::parse(&text).is_some() { String::new() } else { gettext("Not a color") };
+        Row::new()
+            .push(Text::new(feedback).size(12))
+            .push(TextInput::new("#rrggbb", &text).on_input(move |text| Message::SlotText(slot, text)).on_submit(Message::CommitSlotText).width(Length::Fixed(150.0)))
+            .push(Button::new(Text::new(gettext("Copy")).size(12)).on_press(Message::CopySlot(slot)))
+            .push(Button::new(Text::new(gettext("Paste")).size(12)).on_press(Message::PasteSlot(slot)))
+            .align_items(iced::Alignment::Center).spacing(5)
+    }
+    /// the confirmation Save and Activate ask for before replacing a `theme.toml` they couldn't fully read
+    fn overwrite_prompt(&self) -> Row<'_, Message> {
+        let prompt = match self.activating {
+            Some(_) => gettext("Activating replaces theme.toml, which this version can't keep as it is:"),
+            None => gettext("Saving replaces theme.toml, which this version can't keep as it is:"),
+        };
+        match &self.overwrite {
+            Some(risk) => Row::new()
+                .push(Text::new(format!("{prompt} {risk}")))
+                .push(Space::new(Length::Fill, 10))
+                .push(Button::new(Text::new(gettext("Overwrite"))).on_press(Message::ConfirmOverwrite))
+                .push(Button::new(Text::new(gettext("Cancel"))).on_press(Message::CancelOverwrite))
+                .align_items(iced::Alignment::Center).spacing(10),
+            None => Row::new()
+        }
+    }
+    /// the prompt for an outside change to `theme.toml` that clashes with unsaved edits
+    fn external_change(&self) -> Row<'_, Message> {
+        match &self.external {
+            Some(_) => Row::new()
+                .push(Text::new(gettext("theme.toml was changed outside Tetra while you had unsaved edits")))
+                .push(Space::new(Length::Fill, 10))
+                .push(Button::new(Text::new(gettext("Merge"))).on_press(Message::MergeExternal))
+                .push(Button::new(Text::new(gettext("Use Theirs"))).on_press(Message::UseExternal))
+                .push(Button::new(Text::new(gettext("Keep Mine"))).on_press(Message::KeepLocal))
+                .align_items(iced::Alignment::Center).spacing(10),
+            None => Row::new()
+        }
+    }
+    /// the selected slot is outlined, and is the one the keyboard acts on
+    fn slot_row(&self, slot: ColorSlot) -> Container<'_, Message> {
+        let color = self.palette.get(slot);
+        let button = Button::new("").on_press(Message::OpenPicker(slot)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&color).mk_theme());
+        let picker = ColorPicker::new(self.open_picker == Some(slot), color, button, Message::ClosePicker, Message::SubmitColor);
+        let row = Row::new().push(self.slot_label(slot)).push(Space::new(Length::Fill, 10)).push(self.slot_entry(slot)).push(picker).align_items(iced::Alignment::Center).spacing(10);
+        if slot == self.sample_slot {
+            Container::new(Column::new().push(row).push(self.slot_sliders(slot)).spacing(5)).padding(4).style(iced::theme::Container::Custom(Box::new(FocusStyle)))
+        } else {
+            Container::new(row).padding(4)
+        }
+    }
+    /// OKLCh sliders for the selected slot. iced_aw's picker only reports the color it's submitted with,
+    /// so these are what previews every step of a drag in other apps.
+    fn slot_sliders(&self, slot: ColorSlot) -> Row<'_, Message> {
+        let lch = color::to_oklch(&self.palette.get(slot));
+        let channel = |label: String, index: usize, max: f32| {
+            let slider = Slider::new(0.0..=max, lch[index].min(max), move |value| {
+                let mut lch = lch;
+                lch[index] = value;
+                Message::Drag(slot, lch)
+            }).step(max / 360.0).on_release(Message::DragEnd);
+            Row::new().push(Text::new(label).size(12)).push(slider).align_items(iced::Alignment::Center).spacing(5)
+        };
+        Row::new()
+            .push(channel(gettext("Lightness"), 0, 1.0))
+            .push(channel(gettext("Chroma"), 1, 0.37))
+            .push(channel(gettext("Hue"), 2, 360.0))
+            .spacing(10)
+    }
+    /// every group under a header that collapses it. ANSI and semantic colors are derived, so they're shown rather than edited.
+    fn slot_groups(&self) -> Column<'_, Message> {
+        SlotGroup::ALL.into_iter().fold(Column::new().spacing(10), |column, group| {
+            let collapsed = self.collapsed.contains(&group);
+            let header = format!("{} {}", if collapsed { "▸" } else { "▾" }, group.label());
+            let column = column.push(Button::new(Text::new(header).size(20)).on_press(Message::ToggleGroup(group)).width(Length::Fill));
+            if collapsed {
+                return column;
+            }
+            match group {
+                SlotGroup::Ansi => {
+                    let ansi = self.palette.ansi();
+                    let strip = |label: String, colors: &[Color]| colors.iter().fold(Row::new().push(Text::new(label).width(80)).spacing(4), |row, color| row.push(swatch(*color, 28)));
+                    column.push(strip(gettext("Normal"), &ansi[..8])).push(strip(gettext("Bright"), &ansi[8..]))
+                }
+                SlotGroup::Semantic => {
+                    let mapping = &self.syntax;
+                    let roles = [
+                        (gettext("Error"), self.palette.get(mapping.error), Some(mapping.error)),
+                        (gettext("Warning"), self.palette.get(mapping.warning), Some(mapping.warning)),
+                        (gettext("Info"), self.palette.get(mapping.info), Some(mapping.info)),
+                        (gettext("Hint"), self.palette.get(mapping.hint), Some(mapping.hint)),
+                        (gettext("Muted"), self.palette.muted(), None),
+                    ];
+                    roles.into_iter().fold(column, |column, (role, color, slot)| {
+                        let source = slot.map(|slot| slot.label()).unwrap_or(gettext("Text towards Tertiary Background"));
+                        column.push(Row::new().push(swatch(color, 28)).push(Text::new(role).width(100)).push(Text::new(source).size(12)).align_items(iced::Alignment::Center).spacing(10))
+                    })
+                }
+                group => group.slots().iter().fold(column, |column, slot| column.push(self.slot_row(*slot))),
+            }
+        })
+    }
+    /// a mock window drawn in the palette being edited
+    fn preview(&self) -> Column<'_, Message> {
+        let palette = &self.palette;
+        let pane = |background: Color, lines: Vec<(String, Color)>| {
+            let lines = lines.into_iter().fold(Column::new().spacing(4), |column, (line, color)| column.push(Text::new(line).style(color)));
+            Container::new(lines).padding(12).width(Length::Fill).style(iced::theme::Container::Custom(Box::new(SwatchStyle(background))))
+        };
+        let title = pane(palette.bg3, vec![(self.meta.display_name().to_string(), palette.txt)]);
+        let code = pane(palette.bg1, vec![
+            (String::from("fn main() {"), palette.purple),
+            (String::from("    // say hello"), palette.muted()),
+            (String::from("    let answer = 42;"), palette.orange),
+            (String::from("    println!(\"hello\");"), palette.green),
+            (String::from("}"), palette.txt),
+        ]);
+        let messages = pane(palette.bg2, vec![
+            (gettext("error: something broke"), palette.red),
+            (gettext("warning: something looks off"), palette.yellow),
+            (gettext("note: all good"), palette.blue),
+        ]);
+        Column::new()
+            .push(Text::new(gettext("Preview")).size(20))
+            .push(Column::new().push(title).push(code).push(messages))
+            .push(swatch_strip(palette, 24))
+            .spacing(10)
+    }
+    fn tools(&self) -> Column<'_, Message> {
+        let save = Button::new(Text::new(gettext("Save"))).on_press(Message::Save);
+        let save_variant = Button::new(Text::new(match self.meta.variant_of(&self.palette) {
+            Variant::Light => gettext("Save as Light Variant"),
+            Variant::Dark => gettext("Save as Dark Variant"),
+        })).on_press(Message::SaveVariant);
+        let legacy_format = Checkbox::new(gettext("Write for older Oceania apps"), self.legacy_format, Message::LegacyFormat);
+        let save_row = Row::new().align_items(iced::Alignment::Center).push(save).push(save_variant).push(Space::new(Length::Fill, 10)).push(legacy_format).spacing(10);
+        let meta_name = TextInput::new(&gettext("Theme name"), &self.meta.name).on_input(Message::MetaName);
+        let meta_author = TextInput::new(&gettext("Author"), &self.meta.author).on_input(Message::MetaAuthor);
+        let meta_version = TextInput::new(&gettext("Version"), &self.meta.version).on_input(Message::MetaVersion).width(100);
+        let meta_row = Row::new().push(meta_name).push(meta_author).push(meta_version).spacing(10);
+        let meta_variant = PickList::new(vec![Variant::Light, Variant::Dark], self.meta.variant, Message::MetaVariant).placeholder(self.palette.variant().to_string());
+        let meta_license = TextInput::new(&gettext("License"), &self.meta.license).on_input(Message::MetaLicense);
+        let meta_tags = TextInput::new(&gettext("Tags, comma separated"), &self.meta_tags).on_input(Message::MetaTags);
+        let meta_details = Row::new().push(meta_variant).push(meta_license).push(meta_tags).align_items(iced::Alignment::Center).spacing(10);
+        let meta_description = TextInput::new(&gettext("Description"), &self.meta.description).on_input(Message::MetaDescription);
+        let bases: Vec<String> = self.library.iter().map(|entry| entry.name.clone()).filter(|name| self.library_selected.as_ref() != Some(name)).collect();
+        let base_list = PickList::new(bases, self.extends.clone(), Message::SelectBase).placeholder(gettext("No base theme"));
+        let mut base_row = Row::new().push(Text::new(gettext("Extends"))).push(base_list).align_items(iced::Alignment::Center).spacing(10);
+        if self.extends.is_some() {
+            base_row = base_row.push(Button::new(Text::new(gettext("Detach"))).on_press(Message::DetachBase));
+        }
+        let export_list = PickList::new(ExportTarget::ALL.to_vec(), Some(self.export_target), Message::SelectExport);
+        let export_but = Button::new(Text::new(gettext("Export"))).on_press(Message::Export);
+        let export_row = Row::new().push(export_list).push(export_but).push(Space::new(Length::Fill, 10)).align_items(iced::Alignment::Center).spacing(10);
+        let swatch_list = PickList::new(SwatchFormat::ALL.to_vec(), Some(self.swatch_format), Message::SelectSwatchFormat);
+        let swatch_default = self.swatch_format.default_path().display().to_string();
+        let swatch_input = TextInput::new(&swatch_default, &self.swatch_path).on_input(Message::SwatchPath);
+        let swatch_import = Button::new(Text::new(gettext("Import"))).on_press(Message::ImportSwatches);
+        let swatch_export = Button::new(Text::new(gettext("Export"))).on_press(Message::ExportSwatches);
+        let swatch_row = Row::new().push(swatch_list).push(swatch_input).push(swatch_import).push(swatch_export).align_items(iced::Alignment::Center).spacing(10);
+        let terminal_default = format!("{} {}", gettext("Xresources or pywal colors.json, default"), wal::default_import_path().display());
+        let terminal_input = TextInput::new(&terminal_default, &self.terminal_path).on_input(Message::TerminalPath).on_submit(Message::ImportTerminalColors);
+        let terminal_import = Button::new(Text::new(gettext("Import Terminal Colors"))).on_press(Message::ImportTerminalColors);
+        let terminal_row = Row::new().push(terminal_input).push(terminal_import).align_items(iced::Alignment::Center).spacing(10);
+        let bundle_input = TextInput::new(&gettext("Bundle file (.tetra)"), &self.bundle_path).on_input(Message::BundlePath).on_submit(Message::ImportBundle);
+        let bundle_import = Button::new(Text::new(gettext("Import Bundle"))).on_press(Message::ImportBundle);
+        let bundle_export = Button::new(Text::new(gettext("Export Bundle"))).on_press(Message::ExportBundle);
+        let copy_code = Button::new(Text::new(gettext("Copy Theme Code"))).on_press(Message::CopyThemeCode);
+        let paste_code = Button::new(Text::new(gettext("Paste Theme Code"))).on_press(Message::PasteThemeCode);
+        let bundle_row = Row::new().push(bundle_input).push(bundle_import).push(bundle_export).push(copy_code).push(paste_code).align_items(iced::Alignment::Center).spacing(10);
+        let bundle_conflict = match &self.pending_bundle {
+            Some(bundle) => Row::new()
+                .push(Text::new(format!("{} {}", gettext("A theme with that name already exists:"), bundle.name)))
+                .push(Space::new(Length::Fill, 10))
+                .push(Button::new(Text::new(gettext("Keep Both"))).on_press(Message::InstallBundle(Conflict::KeepBoth)))
+                .push(Button::new(Text::new(gettext("Replace"))).on_press(Message::InstallBundle(Conflict::Replace)))
+                .push(Button::new(Text::new(gettext("Cancel"))).on_press(Message::CancelBundle))
+                .align_items(iced::Alignment::Center).spacing(10),
+            None => Row::new()
+        };
+        let wallpaper_input = TextInput::new(&gettext("Wallpaper image (PNG or JPEG)"), &self.wallpaper_path).on_input(Message::WallpaperPath).on_submit(Message::ExtractPalette);
+        let wallpaper_extract = Button::new(Text::new(gettext("Extract Palette"))).on_press(Message::ExtractPalette);
+        let wallpaper_row = Row::new().push(wallpaper_input).push(wallpaper_extract).align_items(iced::Alignment::Center).spacing(10);
+        let candidates = self.candidates.iter().enumerate().fold(Column::new().spacing(5), |column, (i, candidate)| {
+            let apply = Button::new(Text::new(gettext("Apply"))).on_press(Message::ApplyCandidate(i));
+            column.push(Row::new().push(Text::new(&candidate.name)).push(Space::new(Length::Fill, 10)).push(swatch_strip(&candidate.palette, 20)).push(apply).align_items(iced::Alignment::Center).spacing(10))
+        });
+        let candidates = if self.candidates.is_empty() {
+            candidates
+        } else {
+            candidates.push(Button::new(Text::new(gettext("Discard"))).on_press(Message::DiscardCandidates))
+        };
+        let eyedropper_input = TextInput::new(&gettext("Image to sample colors from"), &self.eyedropper_path).on_input(Message::EyedropperPath).on_submit(Message::LoadEyedropperImage);
+        let eyedropper_load = Button::new(Text::new(gettext("Open Image"))).on_press(Message::LoadEyedropperImage);
+        let eyedropper_row = Row::new().push(eyedropper_input).push(eyedropper_load).align_items(iced::Alignment::Center).spacing(10);
+        let eyedropper_panel = match &self.eyedropper_image {
+            Some(image) => {
+                let canvas = Canvas::new(Eyedropper { image, viewport: self.eyedropper_viewport, sample_size: self.sample_size }).width(Length::Fill).height(300);
+                let slot_list = PickList::new(ColorSlot::ALL.to_vec(), Some(self.sample_slot), Message::SelectSampleSlot);
+                let size_list = PickList::new(vec![1, 3, 5, 9], Some(self.sample_size), Message::SelectSampleSize);
+                let reset = Button::new(Text::new(gettext("Fit"))).on_press(Message::ResetEyedropperViewport);
+                let close = Button::new(Text::new(gettext("Close"))).on_press(Message::CloseEyedropper);
+                let controls = Row::new().push(Text::new(gettext("Sample into"))).push(slot_list).push(Text::new(gettext("Average of"))).push(size_list).push(Space::new(Length::Fill, 10)).push(reset).push(close).align_items(iced::Alignment::Center).spacing(10);
+                Column::new().push(canvas).push(controls).spacing(5)
+            }
+            None => Column::new()
+        };
+        let compare_toggle = Button::new(Text::new(if self.show_compare { gettext("Hide Comparison") } else { gettext("Compare Themes") })).on_press(Message::ToggleCompare);
+        let mut compare_panel = Column::new().push(compare_toggle).spacing(5);
+        if self.show_compare {
+            let mut sources = vec![CompareSource::Current, CompareSource::Saved, CompareSource::Variant(Variant::Light), CompareSource::Variant(Variant::Dark)];
+            sources.extend(self.library.iter().map(|entry| CompareSource::Library(entry.name.clone())));
+            let left_list = PickList::new(sources.clone(), Some(self.compare_left.clone()), Message::CompareLeft);
+            let right_list = PickList::new(sources, Some(self.compare_right.clone()), Message::CompareRight);
+            let path_input = TextInput::new(&gettext("Or a theme file, e.g. a backup"), &self.compare_path).on_input(Message::ComparePath).on_submit(Message::CompareFile);
+            let sources_row = Row::new().push(left_list).push(Text::new("→")).push(right_list).push(path_input).align_items(iced::Alignment::Center).spacing(10);
+            compare_panel = compare_panel.push(sources_row);
+            let left = self.compare_palette(&self.compare_left, self.compare_loaded.0);
+            let right = self.compare_palette(&self.compare_right, self.compare_loaded.1);
+            if let (Some(left), Some(right)) = (left, right) {
+                for diff in compare::compare(&left, &right) {
+                    let change = if diff.changed() { format!("ΔE {:.1}", diff.delta_e) } else { String::from("=") };
+                    let row = Row::new()
+                        .push(Text::new(diff.slot.label()).width(220))
+                        .push(swatch(diff.left, 20))
+                        .push(Text::new(format!("{} {:.2}:1", color::hex(&diff.left), diff.left_contrast)).width(140))
+                        .push(Text::new("→"))
+                        .push(swatch(diff.right, 20))
+                        .push(Text::new(format!("{} {:.2}:1", color::hex(&diff.right), diff.right_contrast)).width(140))
+                        .push(Text::new(change))
+                        .align_items(iced::Alignment::Center).spacing(10);
+                    compare_panel = compare_panel.push(row);
+                }
+            }
+        }
+        let blend_toggle = Button::new(Text::new(if self.show_blend { gettext("Hide Blend") } else { gettext("Blend Themes") })).on_press(Message::ToggleBlend);
+        let mut blend_panel = Column::new().push(blend_toggle).spacing(5);
+        if self.show_blend {
+            let mut sources = vec![CompareSource::Current, CompareSource::Saved, CompareSource::Variant(Variant::Light), CompareSource::Variant(Variant::Dark)];
+            sources.extend(self.library.iter().map(|entry| CompareSource::Library(entry.name.clone())));
+            let from_list = PickList::new(sources.clone(), Some(self.blend_from.clone()), Message::BlendFrom);
+            let to_list = PickList::new(sources, Some(self.blend_to.clone()), Message::BlendTo);
+            let amount = Slider::new(0.0..=1.0, self.blend_amount, Message::BlendAmount).step(0.01);
+            let percent = Text::new(format!("{}%", (self.blend_amount * 100.0).round()));
+            let blend_row = Row::new().push(from_list).push(amount).push(percent).push(to_list).align_items(iced::Alignment::Center).spacing(10);
+            blend_panel = blend_panel.push(blend_row);
+            if let Some(blended) = self.blended() {
+                let use_blend = Button::new(Text::new(gettext("Use in Editor"))).on_press(Message::UseBlend);
+                let save_blend = Button::new(Text::new(gettext("Save as New Theme"))).on_press(Message::SaveBlend);
+                blend_panel = blend_panel.push(Row::new().push(swatch_strip(&blended, 32)).push(Space::new(Length::Fill, 10)).push(use_blend).push(save_blend).align_items(iced::Alignment::Center).spacing(10));
+            }
+        }
+        let status = Text::new(&self.status);
+        let shortcuts = Text::new(gettext("↑ ↓ select a slot · Enter opens its picker · ← → lightness · Alt+← → hue · Shift for bigger steps · Ctrl+S save · Ctrl+Z undo · Ctrl+C / Ctrl+V copy and paste")).size(12);
+        Column::new().push(meta_row).push(meta_details).push(meta_description).push(base_row).push(save_row).push(self.overwrite_prompt()).push(export_row).push(swatch_row).push(terminal_row).push(bundle_row).push(bundle_conflict).push(wallpaper_row).push(candidates).push(eyedropper_row).push(eyedropper_panel).push(compare_panel).push(blend_panel).push(status).push(shortcuts).align_items(iced::Alignment::Center).spacing(10)
+    }
+    /// what local edits are measured against: `disk`, or the starting preset when there was no file
+    fn baseline(&self) -> theme::Theme {
+        self.disk.clone().unwrap_or_else(fallback_document)
+    }
+    /// the library rewrites `theme.toml` when its base is renamed or deleted, so the open document and
+    /// what we know is on disk follow along instead of showing up as an outside change
+    fn base_moved(&mut self, from: &str, to: Option<&str>) {
+        if self.extends.as_deref() == Some(from) {
+            self.extends = to.map(str::to_string);
+            self.base = self.base.filter(|_| to.is_some());
+        }
+        if let Some(disk) = self.disk.as_mut().filter(|disk| disk.extends.as_deref() == Some(from)) {
+            disk.extends = to.map(str::to_string);
+        }
+    }
+    fn unsaved(&self) -> bool {
+        self.document() != self.baseline()
+    }
+    fn save(&mut self) {
+        if self.write_disk(self.document(), self.schema_version()).is_ok() {
+            self.external = None;
+            self.broadcast(self.palette, true);
+        }
+    }
+    fn activate(&mut self, selected: &str) {
+        self.status = match Library::home().load(selected) {
+            Ok(theme) => match self.write_disk(theme.clone(), SCHEMA_VERSION) {
+                Ok(()) => {
+                    self.broadcast(theme.palette, true);
+                    format!("{} {selected}", gettext("Activated"))
+                }
+                Err(error) => error.to_string()
+            },
+            Err(error) => error
+        };
+    }
+    /// an undo step back to `palette`
+    fn remember(&mut self, palette: ThemePalette) {
+        self.undo.push(palette);
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+    /// writes `theme.toml`, remembering the document as the copy on disk
+    fn write_disk(&mut self, theme: theme::Theme, schema_version: i64) -> std::io::Result<()> {
+        let text = theme::to_string_as(&theme, schema_version);
+        std::fs::write(theme_path(), &text)?;
+        self.written = Some(text);
+        self.disk = Some(theme);
+        Ok(())
+    }
+    /// a three-way merge against the baseline: slots and metadata edited here win, the rest comes from `theirs`
+    fn merged(&self, theirs: &theme::Theme) -> theme::Theme {
+        let mine = self.document();
+        let base = self.baseline();
+        let mut theme = theirs.clone();
+        for slot in ColorSlot::ALL {
+            if base.palette.get(slot) != mine.palette.get(slot) {
+                theme.palette.set(slot, mine.palette.get(slot));
+            }
+        }
+        if base.meta != mine.meta {
+            theme.meta = mine.meta;
+        }
+        if base.extends != mine.extends {
+            theme.extends = mine.extends;
+        }
+        theme
+    }
+    /// pushes a palette to apps subscribed through `tetra::live`
+    fn broadcast(&self, palette: ThemePalette, saved: bool) {
+        if let Some(live) = &self.live {
+            live.send(&Update { saved, colors: palette.to_file() });
+        }
+    }
+    /// the schema Save writes; 1 is the bare color table older Oceania apps expect
+    fn schema_version(&self) -> i64 {
+        if self.legacy_format { 1 } else { SCHEMA_VERSION }
+    }
+    /// refreshes the library after an action and selects the theme it produced
+    fn library_result(&mut self, result: std::result::Result<String, String>) {
+        match result {
+            Ok(name) => {
+                self.library_name = name.clone();
+                self.library_selected = Some(name);
+                self.status = String::new();
+            }
+            Err(error) => self.status = error
+        }
+        self.library = Library::home().list();
+        self.confirm_delete = false;
+    }
+}
+
 impl Application for Configurator {
     type Executor = iced::executor::Default;
     type Message = Message;
@@ -341,128 +1032,488 @@ impl Application for Configurator {
         gettext("Tetra Theme Tool")
     }
     fn update(&mut self, message: Self::Message) -> iced::Command<Message> {
+        let before = self.palette;
+        let history = matches!(message, Message::Undo | Message::Redo | Message::Drag(..));
         match message {
-            Message::OpenPicker(value) => self.open_picker = Some(value),
+            Message::OpenPicker(value) => {
+                self.open_picker = Some(value);
+                self.sample_slot = value;
+            }
             Message::SubmitColor(value) => {
-                match self.open_picker.as_ref().unwrap() {
-                    ColorSlot::Bg1 => self.bg1 = value,
-                    ColorSlot::Bg2 => self.bg2 = value,
-                    ColorSlot::Bg3 => self.bg3 = value,
-                    ColorSlot::Txt => self.txt = value,
-                    ColorSlot::Red => self.red = value,
-                    ColorSlot::Orange => self.orange = value,
-                    ColorSlot::Yellow => self.yellow = value,
-                    ColorSlot::Green => self.green = value,
-                    ColorSlot::Blue => self.blue = value,
-                    ColorSlot::Purple => self.purple = value,
-                    ColorSlot::Pink => self.pink = value,
-                }
+                self.palette.set(self.open_picker.unwrap_or(self.sample_slot), value);
             }
             Message::ClosePicker => self.open_picker = None,
+            Message::Drag(slot, lch) => {
+                self.drag_start.get_or_insert(before);
+                self.palette.set(slot, color::from_oklch(lch));
+                self.slot_text = None;
+            }
+            Message::DragEnd => {
+                if let Some(start) = self.drag_start.take().filter(|start| *start != self.palette) {
+                    self.remember(start);
+                }
+            }
             Message::Save => {
-                let file = ThemeFile {
-                    bg_color1: string_from_col(&self.bg1),
-                    bg_color2: string_from_col(&self.bg2),
-                    bg_color3: string_from_col(&self.bg3),
-                    txt_color: string_from_col(&self.txt),
-                    red: string_from_col(&self.red),
-                    orange: string_from_col(&self.orange),
-                    yellow: string_from_col(&self.yellow),
-                    green: string_from_col(&self.green),
-                    blue: string_from_col(&self.blue),
-                    purple: string_from_col(&self.purple),
-                    pink: string_from_col(&self.pink),
+                self.activating = None;
+                match theme::overwrite_risk(&theme_path(), &Library::home()) {
+                    Some(risk) => self.overwrite = Some(risk),
+                    None => self.save(),
+                }
+            }
+            Message::ConfirmOverwrite => {
+                self.overwrite = None;
+                match self.activating.take() {
+                    Some(selected) => self.activate(&selected),
+                    None => self.save(),
+                }
+            }
+            Message::CancelOverwrite => {
+                self.overwrite = None;
+                self.activating = None;
+            }
+            Message::SaveVariant => {
+                let variant = self.meta.variant_of(&self.palette);
+                self.status = match theme::write_as(&variant.path(), &self.document(), self.schema_version()) {
+                    Ok(()) => format!("{} {variant}", gettext("Saved variant:")),
+                    Err(error) => format!("{} {error}", gettext("Saving the variant failed:"))
+                };
+            }
+            Message::SelectExport(value) => self.export_target = value,
+            Message::Export => {
+                self.status = match export(self.export_target, &self.palette, &self.meta) {
+                    Ok(paths) => {
+                        let paths: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+                        format!("{} {}", gettext("Exported to"), paths.join(", "))
+                    }
+                    Err(error) => format!("{} {error}", gettext("Export failed:"))
+                };
+            }
+            Message::SelectSwatchFormat(value) => self.swatch_format = value,
+            Message::SwatchPath(value) => self.swatch_path = value,
+            Message::ImportSwatches => {
+                self.status = match swatch::import(Path::new(&self.swatch_path), &self.palette) {
+                    Ok(palette) => {
+                        self.palette = palette;
+                        format!("{} {}", gettext("Imported"), self.swatch_path)
+                    }
+                    Err(error) => format!("{} {error}", gettext("Import failed:"))
+                };
+            }
+            Message::ExportSwatches => {
+                let path = if self.swatch_path.is_empty() {
+                    self.swatch_format.default_path()
+                } else {
+                    PathBuf::from(&self.swatch_path)
+                };
+                let format = SwatchFormat::from_path(&path).unwrap_or(self.swatch_format);
+                self.status = match swatch::export(format, &self.palette, &path) {
+                    Ok(()) => format!("{} {}", gettext("Exported to"), path.display()),
+                    Err(error) => format!("{} {error}", gettext("Export failed:"))
+                };
+            }
+            Message::TerminalPath(value) => self.terminal_path = value,
+            Message::ImportTerminalColors => {
+                let path = if self.terminal_path.is_empty() {
+                    wal::default_import_path()
+                } else {
+                    PathBuf::from(&self.terminal_path)
+                };
+                self.status = match wal::import(&path, &self.palette) {
+                    Ok(palette) => {
+                        self.palette = palette;
+                        format!("{} {}", gettext("Imported"), path.display())
+                    }
+                    Err(error) => format!("{} {error}", gettext("Import failed:"))
+                };
+            }
+            Message::WallpaperPath(value) => self.wallpaper_path = value,
+            Message::ExtractPalette => {
+                match extract::candidates(Path::new(&self.wallpaper_path), &self.palette) {
+                    Ok(candidates) => {
+                        self.candidates = candidates;
+                        self.status = String::new();
+                    }
+                    Err(error) => self.status = format!("{} {error}", gettext("Could not read the image:"))
+                }
+            }
+            Message::ApplyCandidate(index) => {
+                if let Some(candidate) = self.candidates.get(index) {
+                    self.palette = candidate.palette;
+                }
+                self.candidates.clear();
+            }
+            Message::DiscardCandidates => self.candidates.clear(),
+            Message::EyedropperPath(value) => self.eyedropper_path = value,
+            Message::LoadEyedropperImage => {
+                self.images_loaded += 1;
+                match LoadedImage::open(Path::new(&self.eyedropper_path), self.images_loaded) {
+                    Ok(image) => {
+                        self.eyedropper_viewport = Viewport::fit(&image);
+                        self.eyedropper_image = Some(image);
+                        self.status = String::new();
+                    }
+                    Err(error) => self.status = format!("{} {error}", gettext("Could not read the image:"))
+                }
+            }
+            Message::CloseEyedropper => self.eyedropper_image = None,
+            Message::EyedropperViewport(value) => self.eyedropper_viewport = value,
+            Message::ResetEyedropperViewport => {
+                if let Some(image) = &self.eyedropper_image {
+                    self.eyedropper_viewport = Viewport::fit(image);
+                }
+            }
+            Message::SelectSampleSlot(value) => self.sample_slot = value,
+            Message::SelectSampleSize(value) => self.sample_size = value,
+            Message::LibraryName(value) => self.library_name = value,
+            Message::SelectTheme(name) => {
+                match Library::home().load(&name) {
+                    Ok(theme) => self.load_document(theme),
+                    Err(error) => self.status = error
+                }
+                self.library_name = name.clone();
+                self.library_selected = Some(name);
+                self.confirm_delete = false;
+            }
+            Message::NewTheme => {
+                let name = if self.library_name.is_empty() { gettext("Untitled") } else { self.library_name.clone() };
+                self.library_result(Library::home().create(&name, &self.document()));
+            }
+            Message::DuplicateTheme => {
+                if let Some(selected) = self.library_selected.clone() {
+                    self.library_result(Library::home().duplicate(&selected));
+                }
+            }
+            Message::RenameTheme => {
+                if let Some(selected) = self.library_selected.clone() {
+                    let name = self.library_name.trim().to_string();
+                    let renamed = Library::home().rename(&selected, &name);
+                    if renamed.is_ok() {
+                        self.base_moved(&selected, Some(&name));
+                    }
+                    self.library_result(renamed.map(|_| name));
+                }
+            }
+            Message::DeleteTheme => self.confirm_delete = true,
+            Message::ConfirmDeleteTheme => {
+                if let Some(selected) = self.library_selected.take() {
+                    match Library::home().delete(&selected) {
+                        Ok(()) => self.base_moved(&selected, None),
+                        Err(error) => self.status = error
+                    }
+                    self.library = Library::home().list();
+                }
+                self.confirm_delete = false;
+            }
+            Message::StoreTheme => {
+                if let Some(selected) = self.library_selected.clone() {
+                    self.library_result(Library::home().save(&selected, &self.document()).map(|_| selected));
+                }
+            }
+            Message::ActivateTheme => {
+                if let Some(selected) = self.library_selected.clone() {
+                    match theme::overwrite_risk(&theme_path(), &Library::home()) {
+                        Some(risk) => {
+                            self.overwrite = Some(risk);
+                            self.activating = Some(selected);
+                        }
+                        None => self.activate(&selected),
+                    }
+                }
+            }
+            Message::TogglePresets => self.show_presets = !self.show_presets,
+            Message::LoadPreset(index) => {
+                let preset = &presets::PRESETS[index];
+                self.load_document(theme::Theme {
+                    palette: preset.palette(),
+                    meta: ThemeMeta { name: preset.name.to_string(), variant: Some(preset.variant), ..ThemeMeta::default() },
+                    extends: None,
+                });
+                self.library_name = preset.name.to_string();
+                self.library_selected = None;
+                self.status = format!("{} {}", gettext("Loaded preset"), preset.name);
+            }
+            Message::MetaName(value) => self.meta.name = value,
+            Message::MetaAuthor(value) => self.meta.author = value,
+            Message::MetaVersion(value) => self.meta.version = value,
+            Message::MetaVariant(value) => self.meta.variant = Some(value),
+            Message::MetaLicense(value) => self.meta.license = value,
+            Message::MetaDescription(value) => self.meta.description = value,
+            Message::MetaTags(value) => {
+                self.meta.set_tags(&value);
+                self.meta_tags = value;
+            }
+            Message::LegacyFormat(value) => self.legacy_format = value,
+            Message::SelectBase(name) => {
+                let looped = self.library_selected.as_ref().is_some_and(|selected| Library::home().ancestors(&name).contains(selected));
+                if looped {
+                    self.status = format!("{} {name}", gettext("A theme can't extend itself, directly or through"));
+                } else {
+                    match Library::home().load(&name) {
+                        Ok(theme) => {
+                            self.base = Some(theme.palette);
+                            self.extends = Some(name);
+                        }
+                        Err(error) => self.status = error
+                    }
+                }
+            }
+            Message::DetachBase => {
+                self.extends = None;
+                self.base = None;
+            }
+            Message::InheritSlot(slot) => {
+                if let Some(base) = &self.base {
+                    self.palette.set(slot, base.get(slot));
+                }
+            }
+            Message::BundlePath(value) => self.bundle_path = value,
+            Message::ExportBundle => {
+                let name = self.library_selected.clone().unwrap_or(self.meta.display_name().to_string());
+                let path = if self.bundle_path.is_empty() { bundle::default_path(&name) } else { PathBuf::from(&self.bundle_path) };
+                self.status = match bundle::export(&name, &self.document(), &path) {
+                    Ok(()) => format!("{} {}", gettext("Exported to"), path.display()),
+                    Err(error) => format!("{} {error}", gettext("Export failed:"))
                 };
-                let path = format!("{}/Oceania/theme.toml", get_home());
-                let toml_out = toml::to_string(&file).unwrap();
-                let _ = std::fs::write(path, toml_out);
+            }
+            Message::ImportBundle => {
+                match bundle::read(Path::new(&self.bundle_path)) {
+                    Ok(bundle) if Library::home().exists(&bundle.name) => self.pending_bundle = Some(bundle),
+                    Ok(bundle) => self.install_bundle(bundle, Conflict::Fail),
+                    Err(error) => self.status = format!("{} {error}", gettext("Import failed:"))
+                }
+            }
+            Message::InstallBundle(conflict) => {
+                if let Some(bundle) = self.pending_bundle.take() {
+                    self.install_bundle(bundle, conflict);
+                }
+            }
+            Message::CancelBundle => self.pending_bundle = None,
+            Message::CopyThemeCode => {
+                self.status = gettext("Theme code copied");
+                return iced::clipboard::write(code::encode(&self.palette));
+            }
+            Message::ToggleCompare => {
+                self.show_compare = !self.show_compare;
+                self.reload_compare();
+            }
+            Message::CompareLeft(source) => {
+                self.compare_left = source;
+                self.reload_compare();
+            }
+            Message::CompareRight(source) => {
+                self.compare_right = source;
+                self.reload_compare();
+            }
+            Message::ToggleBlend => {
+                self.show_blend = !self.show_blend;
+                self.reload_blend();
+            }
+            Message::BlendFrom(source) => {
+                self.blend_from = source;
+                self.reload_blend();
+            }
+            Message::BlendTo(source) => {
+                self.blend_to = source;
+                self.reload_blend();
+            }
+            Message::BlendAmount(value) => self.blend_amount = value,
+            Message::UseBlend => {
+                if let Some(palette) = self.blended() {
+                    self.palette = palette;
+                }
+            }
+            Message::SaveBlend => {
+                if let Some(palette) = self.blended() {
+                    let name = format!("{} {}%", gettext("Blend"), (self.blend_amount * 100.0).round());
+                    let theme = theme::Theme { palette, meta: ThemeMeta::default(), extends: None };
+                    self.library_result(Library::home().create(&name, &theme));
+                }
+            }
+            Message::ComparePath(value) => self.compare_path = value,
+            Message::CompareFile => {
+                self.compare_left = CompareSource::File(self.compare_path.clone());
+                self.reload_compare();
+            }
+            Message::PasteThemeCode => return iced::clipboard::read(Message::PastedThemeCode),
+            Message::PastedThemeCode(contents) => {
+                self.status = match code::decode(&contents.unwrap_or_default()) {
+                    Ok(palette) => {
+                        self.palette = palette;
+                        gettext("Theme code applied")
+                    }
+                    Err(error) => error
+                };
+            }
+            Message::MoveSelection(offset) => {
+                let index = ColorSlot::ALL.iter().position(|slot| *slot == self.sample_slot).unwrap_or(0) as i32;
+                let slot = ColorSlot::ALL[(index + offset).rem_euclid(ColorSlot::ALL.len() as i32) as usize];
+                self.sample_slot = slot;
+                // keep the selection visible
+                self.collapsed.retain(|group| !group.slots().contains(&slot));
+            }
+            Message::OpenSelected => self.open_picker = Some(self.sample_slot),
+            Message::Nudge(lightness, hue) => {
+                let [l, c, h] = color::to_oklch(&self.palette.get(self.sample_slot));
+                self.palette.set(self.sample_slot, color::from_oklch([(l + lightness).clamp(0.0, 1.0), c, h + hue]));
+                self.slot_text = None;
+            }
+            Message::Undo => {
+                if let Some(palette) = self.undo.pop() {
+                    self.redo.push(self.palette);
+                    self.palette = palette;
+                    self.slot_text = None;
+                }
+            }
+            Message::Redo => {
+                if let Some(palette) = self.redo.pop() {
+                    self.undo.push(self.palette);
+                    self.palette = palette;
+                    self.slot_text = None;
+                }
+            }
+            Message::ToggleGroup(group) => {
+                match self.collapsed.iter().position(|collapsed| *collapsed == group) {
+                    Some(index) => {
+                        self.collapsed.remove(index);
+                    }
+                    None => self.collapsed.push(group)
+                }
+            }
+            Message::SlotText(slot, text) => {
+                // valid text takes effect while typing, so the swatch shows what it parsed to
+                if let Some(color) = color::parse(&text) {
+                    self.palette.set(slot, color);
+                }
+                self.slot_text = Some((slot, text));
+                self.sample_slot = slot;
+            }
+            Message::CommitSlotText => {
+                if self.slot_text.as_ref().is_some_and(|(_, text)| color::parse(text).is_some()) {
+                    self.slot_text = None;
+                }
+            }
+            Message::CopySlot(slot) => {
+                let hex = color::hex(&self.palette.get(slot));
+                self.sample_slot = slot;
+                self.status = format!("{} {hex}", gettext("Copied"));
+                return iced::clipboard::write(hex);
+            }
+            Message::PasteSlot(slot) => return iced::clipboard::read(move |contents| Message::PastedSlot(slot, contents)),
+            Message::PastedSlot(slot, contents) => {
+                match contents.as_deref().and_then(color::parse) {
+                    Some(color) => {
+                        self.palette.set(slot, color);
+                        self.slot_text = None;
+                        self.sample_slot = slot;
+                    }
+                    None => self.status = gettext("The clipboard doesn't hold a color")
+                }
+            }
+            Message::CopySelected => return self.update(Message::CopySlot(self.sample_slot)),
+            Message::PasteSelected => return self.update(Message::PasteSlot(self.sample_slot)),
+            Message::ThemeFileChanged => {
+                let ours = self.written.is_some() && std::fs::read_to_string(theme_path()).ok() == self.written;
+                match theme::load(&theme_path(), &Library::home()) {
+                    Ok(theirs) if !ours && self.disk.as_ref() != Some(&theirs) => {
+                        if self.unsaved() {
+                            self.external = Some(theirs);
+                        } else {
+                            self.load_document(theirs.clone());
+                            self.disk = Some(theirs);
+                            self.status = gettext("Reloaded theme.toml after an outside change");
+                        }
+                    }
+                    // our own save, or a half written file that another event will follow
+                    _ => {}
+                }
+            }
+            Message::UseExternal => {
+                if let Some(theirs) = self.external.take() {
+                    self.load_document(theirs.clone());
+                    self.disk = Some(theirs);
+                }
+            }
+            Message::KeepLocal => {
+                // the next save overwrites the outside change knowingly
+                if let Some(theirs) = self.external.take() {
+                    self.disk = Some(theirs);
+                }
+            }
+            Message::MergeExternal => {
+                if let Some(theirs) = self.external.take() {
+                    self.load_document(self.merged(&theirs));
+                    self.disk = Some(theirs);
+                    self.status = gettext("Merged the outside change with your edits, save to keep the result");
+                }
             }
         }
+        // every edit goes out as a preview, slider drags included
+        if self.palette != before {
+            if !history {
+                self.remember(before);
+            }
+            self.broadcast(self.palette, self.disk.as_ref().map(|disk| disk.palette) == Some(self.palette));
+        }
         iced::Command::none()
     }
+    fn subscription(&self) -> iced::Subscription<Message> {
+        iced::Subscription::batch([
+            watch::changes(PathBuf::from(theme_path())).map(|_| Message::ThemeFileChanged),
+            iced::subscription::events_with(shortcut),
+        ])
+    }
     fn view(&self) -> iced::Element<'_, Self::Message> {
-        let bg1_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Bg1)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.bg1).mk_theme());
-        let bg1_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Bg1,
-            None => false
-        }, self.bg1, bg1_but, Message::ClosePicker, Message::SubmitColor);
-        let bg1label = Text::new(gettext("Primary Background Color"));
-        let bg1_row = Row::new().push(bg1label).push(Space::new(Length::Fill, 10)).push(bg1_picker).align_items(iced::Alignment::Center);
-        let bg2_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Bg2)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.bg2).mk_theme());
-        let bg2_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Bg2,
-            None => false
-        }, self.bg2, bg2_but, Message::ClosePicker, Message::SubmitColor);
-        let bg2label = Text::new(gettext("Secondary Background Color"));
-        let bg2_row = Row::new().push(bg2label).push(Space::new(Length::Fill, 10)).push(bg2_picker).align_items(iced::Alignment::Center).spacing(10);
-        let bg3_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Bg3)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.bg3).mk_theme());
-        let bg3_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Bg3,
-            None => false
-        }, self.bg3, bg3_but, Message::ClosePicker, Message::SubmitColor);
-        let bg3label = Text::new(gettext("Tertiary Background Color"));
-        let bg3_row = Row::new().push(bg3label).push(Space::new(Length::Fill, 10)).push(bg3_picker).align_items(iced::Alignment::Center).spacing(10);
-        let txt_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Txt)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.txt).mk_theme());
-        let txt_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Txt,
-            None => false
-        }, self.txt, txt_but, Message::ClosePicker, Message::SubmitColor);
-        let txt_label = Text::new(gettext("Text Color"));
-        let txt_row = Row::new().push(txt_label).push(Space::new(Length::Fill, 10)).push(txt_picker).align_items(iced::Alignment::Center).spacing(10);
-        let red_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Red)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.red).mk_theme());
-        let red_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Red,
-            None => false
-        }, self.red, red_but, Message::ClosePicker, Message::SubmitColor);
-        let red_label = Text::new(gettext("Red Color"));
-        let red_row = Row::new().push(red_label).push(Space::new(Length::Fill, 10)).push(red_picker).align_items(iced::Alignment::Center).spacing(10);
-        let orange_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Orange)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.orange).mk_theme());
-        let orange_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Orange,
-            None => false
-        }, self.orange, orange_but, Message::ClosePicker, Message::SubmitColor);
-        let orange_label = Text::new(gettext("Orange Color"));
-        let orange_row = Row::new().push(orange_label).push(Space::new(Length::Fill, 10)).push(orange_picker).align_items(iced::Alignment::Center).spacing(10);
-        let yellow_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Yellow)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.yellow).mk_theme());
-        let yellow_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Yellow,
-            None => false
-        }, self.yellow, yellow_but, Message::ClosePicker, Message::SubmitColor);
-        let yellow_label = Text::new(gettext("Yellow Color"));
-        let yellow_row = Row::new().push(yellow_label).push(Space::new(Length::Fill, 10)).push(yellow_picker).align_items(iced::Alignment::Center).spacing(10);
-        let green_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Green)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.green).mk_theme());
-        let green_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Green,
-            None => false
-        }, self.green, green_but, Message::ClosePicker, Message::SubmitColor);
-        let green_label = Text::new(gettext("Green Color"));
-        let green_row = Row::new().push(green_label).push(Space::new(Length::Fill, 10)).push(green_picker).align_items(iced::Alignment::Center).spacing(10);
-        let blue_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Blue)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.blue).mk_theme());
-        let blue_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Blue,
-            None => false
-        }, self.blue, blue_but, Message::ClosePicker, Message::SubmitColor);
-        let blue_label = Text::new(gettext("Blue Color"));
-        let blue_row = Row::new().push(blue_label).push(Space::new(Length::Fill, 10)).push(blue_picker).align_items(iced::Alignment::Center).spacing(10);
-        let purple_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Purple)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.purple).mk_theme());
-        let purple_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Purple,
-            None => false
-        }, self.purple, purple_but, Message::ClosePicker, Message::SubmitColor);
-        let purple_label = Text::new(gettext("Purple Color"));
-        let purple_row = Row::new().push(purple_label).push(Space::new(Length::Fill, 10)).push(purple_picker).align_items(iced::Alignment::Center).spacing(10);
-        let pink_but = Button::new("").on_press(Message::OpenPicker(ColorSlot::Pink)).width(COLOR_SIZE).height(COLOR_SIZE).style(button_style_from_col(&self.pink).mk_theme());
-        let pink_picker = ColorPicker::new(match &self.open_picker {
-            Some(value) => value == &ColorSlot::Pink,
-            None => false
-        }, self.pink, pink_but, Message::ClosePicker, Message::SubmitColor);
-        let pink_label = Text::new(gettext("Pink Color"));
-        let pink_row = Row::new().push(pink_label).push(Space::new(Length::Fill, 10)).push(pink_picker).align_items(iced::Alignment::Center).spacing(10);
-        let save = Button::new(Text::new(gettext("Save"))).on_press(Message::Save);
-        let save_row = Row::new().align_items(iced::Alignment::Start).push(save).push(Space::new(Length::Fill, 10));
+        let themes = self.library.iter().fold(Column::new().spacing(5), |column, theme| {
+            let selected = self.library_selected.as_ref() == Some(&theme.name);
+            let label = if selected { format!("> {}", theme.name) } else { theme.name.clone() };
+            let mut entry = Column::new().push(Text::new(label)).spacing(3);
+            if !theme.meta.author.is_empty() {
+                entry = entry.push(Text::new(format!("{} {}", gettext("by"), theme.meta.author)).size(12));
+            }
+            let entry = entry.push(swatch_strip(&theme.palette, 16));
+            column.push(Button::new(entry).on_press(Message::SelectTheme(theme.name.clone())).width(Length::Fill))
+        });
+        let library_input = TextInput::new(&gettext("Theme name"), &self.library_name).on_input(Message::LibraryName).on_submit(Message::NewTheme);
+        let mut library_actions = Column::new().push(library_input).push(Button::new(Text::new(gettext("New from Current"))).on_press(Message::NewTheme)).spacing(5);
+        if self.library_selected.is_some() {
+            let delete = if self.confirm_delete {
+                Button::new(Text::new(gettext("Really Delete?"))).on_press(Message::ConfirmDeleteTheme)
+            } else {
+                Button::new(Text::new(gettext("Delete"))).on_press(Message::DeleteTheme)
+            };
+            library_actions = library_actions
+                .push(Row::new().push(Button::new(Text::new(gettext("Rename"))).on_press(Message::RenameTheme)).push(Button::new(Text::new(gettext("Duplicate"))).on_press(Message::DuplicateTheme)).spacing(5))
+                .push(Row::new().push(Button::new(Text::new(gettext("Store Current"))).on_press(Message::StoreTheme)).push(delete).spacing(5))
+                .push(Button::new(Text::new(gettext("Activate"))).on_press(Message::ActivateTheme));
+        }
+        let presets_label = if self.show_presets { gettext("Hide Presets") } else { gettext("Show Presets") };
+        let mut sidebar = Column::new().push(Text::new(gettext("Themes")).size(24)).push(Scrollable::new(themes).height(Length::Fill)).push(library_actions).push(Button::new(Text::new(presets_label)).on_press(Message::TogglePresets)).spacing(10).width(260).padding(10);
+        if self.show_presets {
+            let gallery = presets::PRESETS.iter().enumerate().fold(Column::new().spacing(5), |column, (index, preset)| {
+                let name = match preset.variant {
+                    Variant::Light => format!("{} ({})", preset.name, gettext("light")),
+                    Variant::Dark => format!("{} ({})", preset.name, gettext("dark")),
+                };
+                let entry = Column::new().push(Text::new(name)).push(swatch_strip(&preset.palette(), 16)).spacing(3);
+                column.push(Button::new(entry).on_press(Message::LoadPreset(index)).width(Length::Fill))
+            });
+            sidebar = sidebar.push(Scrollable::new(gallery).height(Length::Fill));
+        }
 
-        let master = Column::new().push(bg1_row).push(bg2_row).push(bg3_row).push(txt_row).push(red_row).push(orange_row).push(yellow_row).push(green_row).push(blue_row).push(purple_row).push(pink_row).push(save_row).align_items(iced::Alignment::Center).spacing(10);
-        Container::new(master).center_x().center_y().width(Length::Fill).height(Length::Fill).into()
+        // the preview moves beside the editor once there's room for both
+        let editor = responsive(move |size| {
+            if size.width >= WIDE_LAYOUT {
+                let editor = Column::new().push(self.external_change()).push(self.slot_groups()).push(self.tools()).spacing(20).padding(20);
+                Row::new()
+                    .push(Scrollable::new(editor).width(Length::FillPortion(3)))
+                    .push(Scrollable::new(self.preview().padding(20)).width(Length::FillPortion(2)))
+                    .into()
+            } else {
+                let editor = Column::new().push(self.external_change()).push(self.preview()).push(self.slot_groups()).push(self.tools()).spacing(20).padding(20);
+                Scrollable::new(editor).into()
+            }
+        });
+        Row::new().push(sidebar).push(editor).into()
     }
     fn theme(&self) -> Self::Theme {
         match self.theme_type {
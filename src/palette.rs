@@ -0,0 +1,268 @@
+use std::fmt;
+use iced::Color;
+use gettextrs::gettext;
+use oceania_style::{get_home, string_to_color, ThemeFile};
+use serde_derive::{Serialize, Deserialize};
+use crate::string_from_col;
+use crate::library::Library;
+use crate::color::{is_dark, lighten, mix, mix_oklab};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSlot {
+    Bg1,
+    Bg2,
+    Bg3,
+    Txt,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Pink
+}
+
+impl ColorSlot {
+    pub const ALL: [ColorSlot; 11] = [
+        ColorSlot::Bg1,
+        ColorSlot::Bg2,
+        ColorSlot::Bg3,
+        ColorSlot::Txt,
+        ColorSlot::Red,
+        ColorSlot::Orange,
+        ColorSlot::Yellow,
+        ColorSlot::Green,
+        ColorSlot::Blue,
+        ColorSlot::Purple,
+        ColorSlot::Pink,
+    ];
+    /// the key used for this slot in theme files and generated configs
+    pub fn key(&self) -> &'static str {
+        match self {
+            ColorSlot::Bg1 => "bg1",
+            ColorSlot::Bg2 => "bg2",
+            ColorSlot::Bg3 => "bg3",
+            ColorSlot::Txt => "txt",
+            ColorSlot::Red => "red",
+            ColorSlot::Orange => "orange",
+            ColorSlot::Yellow => "yellow",
+            ColorSlot::Green => "green",
+            ColorSlot::Blue => "blue",
+            ColorSlot::Purple => "purple",
+            ColorSlot::Pink => "pink",
+        }
+    }
+    pub fn label(&self) -> String {
+        match self {
+            ColorSlot::Bg1 => gettext("Primary Background Color"),
+            ColorSlot::Bg2 => gettext("Secondary Background Color"),
+            ColorSlot::Bg3 => gettext("Tertiary Background Color"),
+            ColorSlot::Txt => gettext("Text Color"),
+            ColorSlot::Red => gettext("Red Color"),
+            ColorSlot::Orange => gettext("Orange Color"),
+            ColorSlot::Yellow => gettext("Yellow Color"),
+            ColorSlot::Green => gettext("Green Color"),
+            ColorSlot::Blue => gettext("Blue Color"),
+            ColorSlot::Purple => gettext("Purple Color"),
+            ColorSlot::Pink => gettext("Pink Color"),
+        }
+    }
+}
+
+/// the editor's sections. ANSI and semantic colors are derived from the slots, so those groups have none of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotGroup {
+    Backgrounds,
+    Text,
+    Accents,
+    Ansi,
+    Semantic,
+}
+
+impl SlotGroup {
+    pub const ALL: [SlotGroup; 5] = [SlotGroup::Backgrounds, SlotGroup::Text, SlotGroup::Accents, SlotGroup::Ansi, SlotGroup::Semantic];
+    pub fn label(&self) -> String {
+        match self {
+            SlotGroup::Backgrounds => gettext("Backgrounds"),
+            SlotGroup::Text => gettext("Text"),
+            SlotGroup::Accents => gettext("Accents"),
+            SlotGroup::Ansi => gettext("ANSI"),
+            SlotGroup::Semantic => gettext("Semantic"),
+        }
+    }
+    pub fn slots(&self) -> &'static [ColorSlot] {
+        match self {
+            SlotGroup::Backgrounds => &[ColorSlot::Bg1, ColorSlot::Bg2, ColorSlot::Bg3],
+            SlotGroup::Text => &[ColorSlot::Txt],
+            SlotGroup::Accents => &[ColorSlot::Red, ColorSlot::Orange, ColorSlot::Yellow, ColorSlot::Green, ColorSlot::Blue, ColorSlot::Purple, ColorSlot::Pink],
+            SlotGroup::Ansi | SlotGroup::Semantic => &[],
+        }
+    }
+}
+
+impl fmt::Display for ColorSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemePalette {
+    pub bg1: Color,
+    pub bg2: Color,
+    pub bg3: Color,
+    pub txt: Color,
+    pub red: Color,
+    pub orange: Color,
+    pub yellow: Color,
+    pub green: Color,
+    pub blue: Color,
+    pub purple: Color,
+    pub pink: Color,
+}
+
+impl ThemePalette {
+    pub fn get(&self, slot: ColorSlot) -> Color {
+        match slot {
+            ColorSlot::Bg1 => self.bg1,
+            ColorSlot::Bg2 => self.bg2,
+            ColorSlot::Bg3 => self.bg3,
+            ColorSlot::Txt => self.txt,
+            ColorSlot::Red => self.red,
+            ColorSlot::Orange => self.orange,
+            ColorSlot::Yellow => self.yellow,
+            ColorSlot::Green => self.green,
+            ColorSlot::Blue => self.blue,
+            ColorSlot::Purple => self.purple,
+            ColorSlot::Pink => self.pink,
+        }
+    }
+    pub fn set(&mut self, slot: ColorSlot, value: Color) {
+        match slot {
+            ColorSlot::Bg1 => self.bg1 = value,
+            ColorSlot::Bg2 => self.bg2 = value,
+            ColorSlot::Bg3 => self.bg3 = value,
+            ColorSlot::Txt => self.txt = value,
+            ColorSlot::Red => self.red = value,
+            ColorSlot::Orange => self.orange = value,
+            ColorSlot::Yellow => self.yellow = value,
+            ColorSlot::Green => self.green = value,
+            ColorSlot::Blue => self.blue = value,
+            ColorSlot::Purple => self.purple = value,
+            ColorSlot::Pink => self.pink = value,
+        }
+    }
+    pub fn from_file(file: &ThemeFile) -> ThemePalette {
+        ThemePalette {
+            bg1: string_to_color(file.bg_color1.clone()),
+            bg2: string_to_color(file.bg_color2.clone()),
+            bg3: string_to_color(file.bg_color3.clone()),
+            txt: string_to_color(file.txt_color.clone()),
+            red: string_to_color(file.red.clone()),
+            orange: string_to_color(file.orange.clone()),
+            yellow: string_to_color(file.yellow.clone()),
+            green: string_to_color(file.green.clone()),
+            blue: string_to_color(file.blue.clone()),
+            purple: string_to_color(file.purple.clone()),
+            pink: string_to_color(file.pink.clone()),
+        }
+    }
+    pub fn to_file(self) -> ThemeFile {
+        ThemeFile {
+            bg_color1: string_from_col(&self.bg1),
+            bg_color2: string_from_col(&self.bg2),
+            bg_color3: string_from_col(&self.bg3),
+            txt_color: string_from_col(&self.txt),
+            red: string_from_col(&self.red),
+            orange: string_from_col(&self.orange),
+            yellow: string_from_col(&self.yellow),
+            green: string_from_col(&self.green),
+            blue: string_from_col(&self.blue),
+            purple: string_from_col(&self.purple),
+            pink: string_from_col(&self.pink),
+        }
+    }
+    /// every slot interpolated towards `other`, `amount` being the share of `other`
+    pub fn blend(&self, other: &ThemePalette, amount: f32) -> ThemePalette {
+        let mut out = *self;
+        for slot in ColorSlot::ALL {
+            out.set(slot, mix_oklab(self.get(slot), other.get(slot), amount));
+        }
+        out
+    }
+    /// text color dimmed towards the background, for comments and inactive elements
+    pub fn muted(&self) -> Color {
+        mix(self.txt, self.bg3, 0.45)
+    }
+    /// the sixteen terminal colors, in the usual black..white, bright black..bright white order
+    pub fn ansi(&self) -> [Color; 16] {
+        let cyan = mix(self.blue, self.green, 0.5);
+        let normal = [self.bg3, self.red, self.green, self.yellow, self.blue, self.purple, cyan, self.txt];
+        let mut out = [self.bg3; 16];
+        for (i, color) in normal.iter().enumerate() {
+            out[i] = *color;
+            out[i + 8] = lighten(*color, 0.12);
+        }
+        out[0] = mix(self.bg3, self.txt, 0.1);
+        out[8] = self.muted();
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    Light,
+    Dark
+}
+
+impl Variant {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Variant::Light => "light",
+            Variant::Dark => "dark",
+        }
+    }
+    pub fn other(&self) -> Variant {
+        match self {
+            Variant::Light => Variant::Dark,
+            Variant::Dark => Variant::Light,
+        }
+    }
+    /// where the saved palette for this variant lives, next to `theme.toml`
+    pub fn path(&self) -> String {
+        format!("{}/Oceania/theme-{}.toml", get_home(), self.key())
+    }
+    pub fn load(&self) -> Option<ThemePalette> {
+        crate::theme::read(&self.path(), &Library::home()).map(|(palette, _)| palette)
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Light => write!(f, "{}", gettext("Light")),
+            Variant::Dark => write!(f, "{}", gettext("Dark")),
+        }
+    }
+}
+
+impl ThemePalette {
+    pub fn variant(&self) -> Variant {
+        if is_dark(&self.bg1) {
+            Variant::Dark
+        } else {
+            Variant::Light
+        }
+    }
+    /// this palette first, followed by the saved palette of the opposite variant if there is one
+    pub fn with_counterpart(&self) -> Vec<(Variant, ThemePalette)> {
+        let variant = self.variant();
+        let mut out = vec![(variant, *self)];
+        if let Some(other) = variant.other().load() {
+            out.push((variant.other(), other));
+        }
+        out
+    }
+}
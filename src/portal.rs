@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+use gettextrs::gettext;
+use serde_derive::{Serialize, Deserialize};
+use zbus::blocking::{Connection, ConnectionBuilder, Proxy};
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::{dbus_interface, fdo, SignalContext};
+use crate::library::Library;
+use crate::palette::{ColorSlot, Variant};
+use crate::theme::{self, theme_path, Theme};
+
+/// the name xdg-desktop-portal looks for when `tetra.portal` lists us as the settings backend
+pub const BUS_NAME: &str = "org.freedesktop.impl.portal.desktop.tetra";
+const OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const APPEARANCE: &str = "org.freedesktop.appearance";
+
+/// drop into `/usr/share/xdg-desktop-portal/portals/` so the portal forwards appearance settings to `tetra portal`
+pub const PORTAL_FILE: &str = "[portal]
+DBusName=org.freedesktop.impl.portal.desktop.tetra
+Interfaces=org.freedesktop.impl.portal.Settings
+UseIn=oceania
+";
+
+/// read from `~/Oceania/tetra/portal.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortalConfig {
+    /// the slot published as `accent-color`
+    pub accent: ColorSlot,
+}
+
+impl Default for PortalConfig {
+    fn default() -> Self {
+        PortalConfig { accent: ColorSlot::Blue }
+    }
+}
+
+impl PortalConfig {
+    pub fn path(library: &Library) -> PathBuf {
+        library.settings_path("portal.toml")
+    }
+    pub fn load(library: &Library) -> Result<PortalConfig, String> {
+        let path = PortalConfig::path(library);
+        match std::fs::read_to_string(&path) {
+            Ok(value) => toml::from_str(&value).map_err(|error| format!("{}: {error}", path.display())),
+            Err(..) => Ok(PortalConfig::default())
+        }
+    }
+}
+
+/// `TETRA_DBUS_ADDRESS` points at a private bus instead of the session bus, for trying things out
+/// under `dbus-daemon --session --print-address`
+fn address() -> Option<String> {
+    std::env::var("TETRA_DBUS_ADDRESS").ok()
+}
+
+/// a connection to the bus at `address`, or the session bus
+fn builder(address: Option<&str>) -> zbus::Result<ConnectionBuilder<'static>> {
+    match address {
+        Some(address) => ConnectionBuilder::address(address),
+        None => ConnectionBuilder::session(),
+    }
+}
+
+/// the appearance namespace as the portal spells it: 1 prefers dark, 2 prefers light
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Appearance {
+    color_scheme: u32,
+    accent_color: (f64, f64, f64),
+}
+
+impl Appearance {
+    fn of(theme: &Theme, accent: ColorSlot) -> Appearance {
+        let color_scheme = match theme.meta.variant.unwrap_or(theme.palette.variant()) {
+            Variant::Dark => 1,
+            Variant::Light => 2,
+        };
+        let color = theme.palette.get(accent);
+        Appearance { color_scheme, accent_color: (color.r as f64, color.g as f64, color.b as f64) }
+    }
+    fn current(accent: ColorSlot, library: &Library) -> Option<Appearance> {
+        theme::load(&library.theme_path(), library).ok().map(|theme| Appearance::of(&theme, accent))
+    }
+    fn values(&self) -> HashMap<String, OwnedValue> {
+        HashMap::from([
+            (String::from("color-scheme"), Value::from(self.color_scheme).into()),
+            (String::from("accent-color"), Value::from(self.accent_color).into()),
+        ])
+    }
+}
+
+/// `org.freedesktop.impl.portal.Settings`, answering only for the appearance namespace
+struct Settings {
+    appearance: Option<Appearance>,
+}
+
+/// namespace filters may end in `*` to match a prefix
+fn matches(namespaces: &[String]) -> bool {
+    namespaces.is_empty() || namespaces.iter().any(|namespace| match namespace.strip_suffix('*') {
+        Some(prefix) => APPEARANCE.starts_with(prefix),
+        None => namespace == APPEARANCE,
+    })
+}
+
+#[dbus_interface(name = "org.freedesktop.impl.portal.Settings")]
+impl Settings {
+    fn read_all(&self, namespaces: Vec<String>) -> HashMap<String, HashMap<String, OwnedValue>> {
+        match self.appearance {
+            Some(appearance) if matches(&namespaces) => HashMap::from([(String::from(APPEARANCE), appearance.values())]),
+            _ => HashMap::new(),
+        }
+    }
+    fn read(&self, namespace: &str, key: &str) -> fdo::Result<OwnedValue> {
+        let not_found = || fdo::Error::UnknownProperty(format!("{namespace} {key}"));
+        let appearance = self.appearance.filter(|_| namespace == APPEARANCE).ok_or_else(not_found)?;
+        appearance.values().remove(key).ok_or_else(not_found)
+    }
+    #[dbus_interface(property, name = "version")]
+    fn version(&self) -> u32 {
+        1
+    }
+    #[dbus_interface(signal)]
+    async fn setting_changed(context: &SignalContext<'_>, namespace: &str, key: &str, value: Value<'_>) -> zbus::Result<()>;
+}
+
+fn modified(library: &Library) -> Option<SystemTime> {
+    std::fs::metadata(library.theme_path()).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// serves the active variant and accent until the process is stopped, announcing every change to `theme.toml`
+pub fn serve() -> Result<(), String> {
+    // the sender lives as long as the process, so this never stops on its own
+    let (_stop, stopped) = mpsc::channel();
+    serve_until(address().as_deref(), &Library::home(), &stopped)
+}
+
+/// serves `library`'s active theme on the bus at `address` until `stop` gets a message or its sender is dropped
+fn serve_until(address: Option<&str>, library: &Library, stop: &Receiver<()>) -> Result<(), String> {
+    let accent = PortalConfig::load(library)?.accent;
+    let connection = builder(address)
+        .and_then(|builder| builder.name(BUS_NAME))
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, Settings { appearance: Appearance::current(accent, library) }))
+        .and_then(|builder| builder.build())
+        .map_err(|error| format!("{} {error}", gettext("Couldn't connect to D-Bus:")))?;
+    let settings = connection.object_server().interface::<_, Settings>(OBJECT_PATH).map_err(|error| error.to_string())?;
+    let mut last_modified = modified(library);
+    loop {
+        if stop.recv_timeout(Duration::from_secs(1)) != Err(RecvTimeoutError::Timeout) {
+            return Ok(());
+        }
+        if modified(library) == last_modified {
+            continue;
+        }
+        last_modified = modified(library);
+        let Some(new) = Appearance::current(accent, library) else {
+            continue;
+        };
+        let old = settings.get_mut().appearance.replace(new);
+        let context = settings.signal_context();
+        if old.map(|old| old.color_scheme) != Some(new.color_scheme) {
+            let _ = zbus::block_on(Settings::setting_changed(context, APPEARANCE, "color-scheme", new.color_scheme.into()));
+        }
+        if old.map(|old| old.accent_color) != Some(new.accent_color) {
+            let _ = zbus::block_on(Settings::setting_changed(context, APPEARANCE, "accent-color", new.accent_color.into()));
+        }
+    }
+}
+
+/// the variant the desktop's portal prefers, or `None` when it has no preference
+fn preference(value: &Value) -> Option<Variant> {
+    match value {
+        // older portals wrap the value in a second variant
+        Value::Value(inner) => preference(inner),
+        Value::U32(1) => Some(Variant::Dark),
+        Value::U32(2) => Some(Variant::Light),
+        _ => None,
+    }
+}
+
+/// makes the saved variant matching the preference the active theme
+fn switch(variant: Variant) -> Result<(), String> {
+    let theme = theme::load(&variant.path(), &Library::home())
+        .map_err(|_| format!("{} {variant}", gettext("There is no saved variant:")))?;
+    if theme::load(&theme_path(), &Library::home()).map(|current| current.palette) == Ok(theme.palette) {
+        return Ok(());
+    }
+    theme::refuse_risky_overwrite(&theme_path(), &Library::home())?;
+    theme::write(&theme_path(), &theme).map_err(|error| error.to_string())
+}
+
+/// follows `color-scheme` from `org.freedesktop.portal.Desktop`, switching between the saved variants.
+/// this reads the desktop's own preference, so it shouldn't be combined with `serve` on the same bus.
+pub fn follow(once: bool) -> Result<(), String> {
+    let dbus_error = |error: zbus::Error| format!("{} {error}", gettext("Couldn't reach the appearance portal:"));
+    let connection: Connection = builder(address().as_deref()).and_then(|builder| builder.build()).map_err(dbus_error)?;
+    let proxy = Proxy::new(&connection, "org.freedesktop.portal.Desktop", OBJECT_PATH, "org.freedesktop.portal.Settings")
+        .map_err(dbus_error)?;
+    let value: OwnedValue = proxy.call("Read", &(APPEARANCE, "color-scheme")).map_err(dbus_error)?;
+    if let Some(variant) = preference(&value) {
+        switch(variant)?;
+    }
+    if once {
+        return Ok(());
+    }
+    for signal in proxy.receive_signal("SettingChanged").map_err(dbus_error)? {
+        let Ok((namespace, key, value)) = signal.body::<(String, String, OwnedValue)>() else {
+            continue;
+        };
+        if namespace != APPEARANCE || key != "color-scheme" {
+            continue;
+        }
+        if let Some(variant) = preference(&value) {
+            // a missing variant shouldn't stop us from following the next change
+            if let Err(error) = switch(variant) {
+                eprintln!("tetra: {error}");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use super::*;
+    use crate::library::tests::{sample, scratch};
+    use crate::theme::ThemeMeta;
+
+    fn theme(variant: Variant) -> Theme {
+        Theme { meta: ThemeMeta { variant: Some(variant), ..ThemeMeta::default() }, ..sample() }
+    }
+
+    /// runs the server against a private bus and talks to it the way xdg-desktop-portal would
+    #[test]
+    fn serves_and_announces_the_appearance() {
+        let Ok(mut daemon) = Command::new("dbus-daemon").args(["--session", "--print-address", "--nofork"])
+            .stdout(Stdio::piped()).stderr(Stdio::null()).spawn() else {
+            eprintln!("dbus-daemon isn't installed, skipping");
+            return;
+        };
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        let address = address.trim().to_string();
+        let library = scratch("portal");
+        theme::write(&library.theme_path(), &theme(Variant::Dark)).unwrap();
+        let (stop, stopped) = mpsc::channel();
+        let server = {
+            let (address, library) = (address.clone(), library.clone());
+            std::thread::spawn(move || serve_until(Some(&address), &library, &stopped))
+        };
+
+        let connection = builder(Some(&address)).and_then(|builder| builder.build()).unwrap();
+        let proxy = Proxy::new(&connection, BUS_NAME, OBJECT_PATH, "org.freedesktop.impl.portal.Settings").unwrap();
+        // the server claims its name on its own thread
+        let read = || proxy.call::<_, _, OwnedValue>("Read", &(APPEARANCE, "color-scheme"));
+        for _ in 0..50 {
+            if read().is_ok() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let value = read().unwrap();
+        assert_eq!(preference(&value), Some(Variant::Dark));
+        assert!(proxy.call::<_, _, OwnedValue>("Read", &(APPEARANCE, "contrast")).is_err());
+
+        let all: HashMap<String, HashMap<String, OwnedValue>> = proxy.call("ReadAll", &(vec!["org.freedesktop.*"],)).unwrap();
+        assert!(all[APPEARANCE].contains_key("accent-color"));
+        let none: HashMap<String, HashMap<String, OwnedValue>> = proxy.call("ReadAll", &(vec!["org.gnome.*"],)).unwrap();
+        assert!(none.is_empty());
+
+        let (sender, receiver) = mpsc::channel();
+        let mut signals = proxy.receive_signal("SettingChanged").unwrap();
+        let listener = std::thread::spawn(move || {
+            if let Some(signal) = signals.next() {
+                let _ = sender.send(signal.body::<(String, String, OwnedValue)>().unwrap());
+            }
+        });
+        theme::write(&library.theme_path(), &theme(Variant::Light)).unwrap();
+        let (namespace, key, value) = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!((namespace.as_str(), key.as_str()), (APPEARANCE, "color-scheme"));
+        assert_eq!(preference(&value), Some(Variant::Light));
+
+        listener.join().unwrap();
+        drop(stop);
+        server.join().unwrap().unwrap();
+        let _ = daemon.kill();
+        let _ = daemon.wait();
+    }
+}
@@ -0,0 +1,115 @@
+use oceania_style::string_to_color;
+use crate::palette::{ThemePalette, Variant};
+
+/// a well known color scheme mapped onto Tetra's slots
+///
+/// colors are in `ColorSlot::ALL` order, bg1 being the deepest background. schemes without
+/// a dedicated accent for a slot borrow their nearest one, so a few presets repeat a color.
+pub struct Preset {
+    pub name: &'static str,
+    pub variant: Variant,
+    colors: [&'static str; 11],
+}
+
+impl Preset {
+    pub fn palette(&self) -> ThemePalette {
+        ThemePalette {
+            bg1: string_to_color(self.colors[0].to_string()),
+            bg2: string_to_color(self.colors[1].to_string()),
+            bg3: string_to_color(self.colors[2].to_string()),
+            txt: string_to_color(self.colors[3].to_string()),
+            red: string_to_color(self.colors[4].to_string()),
+            orange: string_to_color(self.colors[5].to_string()),
+            yellow: string_to_color(self.colors[6].to_string()),
+            green: string_to_color(self.colors[7].to_string()),
+            blue: string_to_color(self.colors[8].to_string()),
+            purple: string_to_color(self.colors[9].to_string()),
+            pink: string_to_color(self.colors[10].to_string()),
+        }
+    }
+}
+
+pub const CATPPUCCIN_MACCHIATO: Preset = Preset {
+    name: "Catppuccin Macchiato",
+    variant: Variant::Dark,
+    colors: ["181926", "1e2030", "24273a", "cad3f5", "ed8796", "f5a97f", "eed49f", "a6da95", "8aadf4", "c6a0f6", "f5bde6"],
+};
+
+pub const PRESETS: [Preset; 16] = [
+    Preset {
+        name: "Catppuccin Latte",
+        variant: Variant::Light,
+        colors: ["dce0e8", "e6e9ef", "eff1f5", "4c4f69", "d20f39", "fe640b", "df8e1d", "40a02b", "1e66f5", "8839ef", "ea76cb"],
+    },
+    Preset {
+        name: "Catppuccin Frappé",
+        variant: Variant::Dark,
+        colors: ["232634", "292c3c", "303446", "c6d0f5", "e78284", "ef9f76", "e5c890", "a6d189", "8caaee", "ca9ee6", "f4b8e4"],
+    },
+    CATPPUCCIN_MACCHIATO,
+    Preset {
+        name: "Catppuccin Mocha",
+        variant: Variant::Dark,
+        colors: ["11111b", "181825", "1e1e2e", "cdd6f4", "f38ba8", "fab387", "f9e2af", "a6e3a1", "89b4fa", "cba6f7", "f5c2e7"],
+    },
+    Preset {
+        name: "Nord",
+        variant: Variant::Dark,
+        colors: ["2e3440", "3b4252", "434c5e", "eceff4", "bf616a", "d08770", "ebcb8b", "a3be8c", "81a1c1", "b48ead", "b48ead"],
+    },
+    Preset {
+        name: "Gruvbox Dark",
+        variant: Variant::Dark,
+        colors: ["1d2021", "282828", "3c3836", "ebdbb2", "fb4934", "fe8019", "fabd2f", "b8bb26", "83a598", "d3869b", "d3869b"],
+    },
+    Preset {
+        name: "Gruvbox Light",
+        variant: Variant::Light,
+        colors: ["ebdbb2", "fbf1c7", "f9f5d7", "3c3836", "9d0006", "af3a03", "b57614", "79740e", "076678", "8f3f71", "8f3f71"],
+    },
+    Preset {
+        name: "Solarized Dark",
+        variant: Variant::Dark,
+        colors: ["00212b", "002b36", "073642", "839496", "dc322f", "cb4b16", "b58900", "859900", "268bd2", "6c71c4", "d33682"],
+    },
+    Preset {
+        name: "Solarized Light",
+        variant: Variant::Light,
+        colors: ["e4ddc8", "eee8d5", "fdf6e3", "657b83", "dc322f", "cb4b16", "b58900", "859900", "268bd2", "6c71c4", "d33682"],
+    },
+    Preset {
+        name: "Dracula",
+        variant: Variant::Dark,
+        colors: ["191a21", "21222c", "282a36", "f8f8f2", "ff5555", "ffb86c", "f1fa8c", "50fa7b", "8be9fd", "bd93f9", "ff79c6"],
+    },
+    Preset {
+        name: "Tokyo Night",
+        variant: Variant::Dark,
+        colors: ["16161e", "1a1b26", "24283b", "c0caf5", "f7768e", "ff9e64", "e0af68", "9ece6a", "7aa2f7", "9d7cd8", "bb9af7"],
+    },
+    Preset {
+        name: "Rosé Pine",
+        variant: Variant::Dark,
+        colors: ["191724", "1f1d2e", "26233a", "e0def4", "eb6f92", "ebbcba", "f6c177", "31748f", "9ccfd8", "c4a7e7", "ebbcba"],
+    },
+    Preset {
+        name: "Rosé Pine Dawn",
+        variant: Variant::Light,
+        colors: ["f2e9e1", "fffaf3", "faf4ed", "575279", "b4637a", "d7827e", "ea9d34", "286983", "56949f", "907aa9", "d7827e"],
+    },
+    Preset {
+        name: "Everforest Dark",
+        variant: Variant::Dark,
+        colors: ["232a2e", "2d353b", "343f44", "d3c6aa", "e67e80", "e69875", "dbbc7f", "a7c080", "7fbbb3", "d699b6", "d699b6"],
+    },
+    Preset {
+        name: "Everforest Light",
+        variant: Variant::Light,
+        colors: ["efebd4", "f4f0d9", "fdf6e3", "5c6a72", "f85552", "f57d26", "dfa000", "8da101", "3a94c5", "df69ba", "df69ba"],
+    },
+    Preset {
+        name: "One Dark",
+        variant: Variant::Dark,
+        colors: ["21252b", "282c34", "2c313a", "abb2bf", "e06c75", "d19a66", "e5c07b", "98c379", "61afef", "c678dd", "c678dd"],
+    },
+];
@@ -0,0 +1,211 @@
+use std::f64::consts::PI;
+use std::time::Duration;
+use chrono::{Datelike, Local, Timelike};
+use gettextrs::gettext;
+use oceania_style::get_home;
+use serde_derive::{Serialize, Deserialize};
+use crate::library::Library;
+use crate::palette::{ThemePalette, Variant};
+use crate::theme::{self, theme_path, Theme, ThemeMeta};
+
+const DAY: f64 = 1440.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleMode {
+    /// switch at `light_at` and `dark_at`
+    Times,
+    /// switch at sunrise and sunset for `latitude`/`longitude`
+    Sun,
+}
+
+/// when `tetra daemon` moves between the light and dark variants, read from `~/Oceania/tetra/schedule.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub mode: ScheduleMode,
+    /// local `HH:MM`
+    pub light_at: String,
+    pub dark_at: String,
+    /// degrees, north and east positive
+    pub latitude: f64,
+    pub longitude: f64,
+    /// how long the blend from one variant to the other takes; 0 switches at once
+    pub transition_minutes: u32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            mode: ScheduleMode::Times,
+            light_at: String::from("07:00"),
+            dark_at: String::from("19:00"),
+            latitude: 0.0,
+            longitude: 0.0,
+            transition_minutes: 0,
+        }
+    }
+}
+
+pub enum Daylight {
+    /// sunrise and sunset in minutes after midnight UTC, possibly outside 0..1440
+    Normal(f64, f64),
+    PolarDay,
+    PolarNight,
+}
+
+/// NOAA's low precision sunrise equation, good to a minute or two away from the poles
+pub fn daylight(day_of_year: u32, latitude: f64, longitude: f64) -> Daylight {
+    let gamma = 2.0 * PI / 365.0 * (day_of_year as f64 - 1.0);
+    let eqtime = 229.18 * (0.000075 + 0.001868 * gamma.cos() - 0.032077 * gamma.sin()
+        - 0.014615 * (2.0 * gamma).cos() - 0.040849 * (2.0 * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin() - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin() - 0.002697 * (3.0 * gamma).cos() + 0.00148 * (3.0 * gamma).sin();
+    let lat = latitude.to_radians();
+    // 90.833° puts the sun's upper edge on the horizon, refraction included
+    let cos_ha = 90.833f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1.0 {
+        return Daylight::PolarNight;
+    }
+    if cos_ha < -1.0 {
+        return Daylight::PolarDay;
+    }
+    let ha = cos_ha.acos().to_degrees();
+    Daylight::Normal(720.0 - 4.0 * (longitude + ha) - eqtime, 720.0 - 4.0 * (longitude - ha) - eqtime)
+}
+
+/// `HH:MM` as minutes after midnight
+pub fn parse_time(text: &str) -> Option<f64> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some((hours * 60 + minutes) as f64)
+}
+
+/// 0 for fully light, 1 for fully dark. each switch starts at its time and takes `transition` minutes.
+pub fn darkness(now: f64, light_at: f64, dark_at: f64, transition: f64) -> f32 {
+    let since_light = (now - light_at).rem_euclid(DAY);
+    let since_dark = (now - dark_at).rem_euclid(DAY);
+    let ramp = |since: f64| if transition <= 0.0 { 1.0 } else { (since / transition).min(1.0) as f32 };
+    if since_light < since_dark {
+        1.0 - ramp(since_light)
+    } else {
+        ramp(since_dark)
+    }
+}
+
+impl Schedule {
+    pub fn path() -> String {
+        format!("{}/Oceania/tetra/schedule.toml", get_home())
+    }
+    pub fn load() -> Result<Schedule, String> {
+        match std::fs::read_to_string(Schedule::path()) {
+            Ok(value) => toml::from_str(&value).map_err(|error| format!("{}: {error}", Schedule::path())),
+            Err(..) => Ok(Schedule::default())
+        }
+    }
+    /// how dark things should be at `now`, a local time in minutes after midnight
+    pub fn darkness_at(&self, now: f64, day_of_year: u32, utc_offset: f64) -> Result<f32, String> {
+        let transition = self.transition_minutes as f64;
+        match self.mode {
+            ScheduleMode::Times => {
+                let invalid = |text: &str| format!("{} {text}", gettext("Not a HH:MM time:"));
+                let light_at = parse_time(&self.light_at).ok_or_else(|| invalid(&self.light_at))?;
+                let dark_at = parse_time(&self.dark_at).ok_or_else(|| invalid(&self.dark_at))?;
+                Ok(darkness(now, light_at, dark_at, transition))
+            }
+            ScheduleMode::Sun => Ok(match daylight(day_of_year, self.latitude, self.longitude) {
+                Daylight::Normal(sunrise, sunset) => darkness(now, sunrise + utc_offset, sunset + utc_offset, transition),
+                Daylight::PolarDay => 0.0,
+                Daylight::PolarNight => 1.0,
+            }),
+        }
+    }
+}
+
+/// the palette for a given darkness, carrying over the metadata of whichever variant is nearer
+fn target(light: &Theme, dark: &Theme, darkness: f32) -> Theme {
+    let nearer = if darkness < 0.5 { light } else { dark };
+    let variant = if darkness < 0.5 { Variant::Light } else { Variant::Dark };
+    Theme {
+        palette: light.palette.blend(&dark.palette, darkness),
+        meta: ThemeMeta { variant: Some(variant), ..nearer.meta.clone() },
+        extends: None,
+    }
+}
+
+/// writes the theme for the current time, returning the palette if it changed
+pub fn apply(last: Option<ThemePalette>) -> Result<Option<ThemePalette>, String> {
+    let schedule = Schedule::load()?;
+    let missing = || gettext("Save a light and a dark variant before scheduling them");
+    let light = theme::load(&Variant::Light.path(), &Library::home()).map_err(|_| missing())?;
+    let dark = theme::load(&Variant::Dark.path(), &Library::home()).map_err(|_| missing())?;
+    let now = Local::now();
+    let minutes = (now.hour() * 60 + now.minute()) as f64 + now.second() as f64 / 60.0;
+    let utc_offset = now.offset().local_minus_utc() as f64 / 60.0;
+    let theme = target(&light, &dark, schedule.darkness_at(minutes, now.ordinal(), utc_offset)?);
+    if last == Some(theme.palette) {
+        return Ok(None);
+    }
+    theme::refuse_risky_overwrite(&theme_path(), &Library::home())?;
+    theme::write(&theme_path(), &theme).map_err(|error| error.to_string())?;
+    Ok(Some(theme.palette))
+}
+
+/// keeps `theme.toml` in step with the schedule until the process is stopped
+pub fn run() -> Result<(), String> {
+    let mut last = None;
+    loop {
+        match apply(last) {
+            Ok(Some(palette)) => last = Some(palette),
+            Ok(None) => {}
+            // a half written schedule or variant shouldn't take the daemon down
+            Err(error) => eprintln!("tetra: {error}"),
+        }
+        std::thread::sleep(Duration::from_secs(30));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn london_at_midsummer() {
+        // 21 June: sunrise 04:43 and sunset 21:21 BST, an hour ahead of UTC
+        let Daylight::Normal(sunrise, sunset) = daylight(172, 51.5, -0.12) else {
+            panic!("London has a sunrise in June");
+        };
+        assert!((sunrise - (3.0 * 60.0 + 43.0)).abs() < 3.0, "{sunrise}");
+        assert!((sunset - (20.0 * 60.0 + 21.0)).abs() < 3.0, "{sunset}");
+    }
+
+    #[test]
+    fn polar_day_and_night() {
+        assert!(matches!(daylight(172, 69.6, 18.9), Daylight::PolarDay));
+        assert!(matches!(daylight(355, 69.6, 18.9), Daylight::PolarNight));
+        assert!(matches!(daylight(355, -69.6, 18.9), Daylight::PolarDay));
+    }
+
+    #[test]
+    fn darkness_ramps_from_each_switch() {
+        let (light, dark) = (7.0 * 60.0, 19.0 * 60.0);
+        assert_eq!(darkness(6.0 * 60.0, light, dark, 30.0), 1.0);
+        assert_eq!(darkness(light, light, dark, 30.0), 1.0);
+        assert_eq!(darkness(light + 15.0, light, dark, 30.0), 0.5);
+        assert_eq!(darkness(light + 30.0, light, dark, 30.0), 0.0);
+        assert_eq!(darkness(dark, light, dark, 30.0), 0.0);
+        assert_eq!(darkness(dark + 15.0, light, dark, 30.0), 0.5);
+        assert_eq!(darkness(dark + 30.0, light, dark, 30.0), 1.0);
+        // past midnight, and switching at once
+        assert_eq!(darkness(30.0, light, dark, 30.0), 1.0);
+        assert_eq!(darkness(light, light, dark, 0.0), 0.0);
+        assert_eq!(darkness(dark, light, dark, 0.0), 1.0);
+    }
+
+    #[test]
+    fn times_parse_as_minutes() {
+        assert_eq!(parse_time(" 07:30 "), Some(450.0));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("7"), None);
+    }
+}
@@ -0,0 +1,480 @@
+use std::fmt;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use iced::Color;
+use gettextrs::gettext;
+use oceania_style::get_home;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::color::rgb8;
+use crate::palette::{ColorSlot, ThemePalette};
+
+/// palette files understood by GIMP, Inkscape, Adobe apps and Krita
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwatchFormat {
+    Gpl,
+    Ase,
+    Aco,
+    Kpl,
+}
+
+/// a named color read from a swatch file
+pub struct Swatch {
+    pub name: Option<String>,
+    pub color: Color,
+}
+
+impl SwatchFormat {
+    pub const ALL: [SwatchFormat; 4] = [
+        SwatchFormat::Gpl,
+        SwatchFormat::Ase,
+        SwatchFormat::Aco,
+        SwatchFormat::Kpl,
+    ];
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SwatchFormat::Gpl => "gpl",
+            SwatchFormat::Ase => "ase",
+            SwatchFormat::Aco => "aco",
+            SwatchFormat::Kpl => "kpl",
+        }
+    }
+    pub fn from_path(path: &Path) -> Option<SwatchFormat> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        SwatchFormat::ALL.into_iter().find(|format| format.extension() == extension)
+    }
+    pub fn default_path(&self) -> PathBuf {
+        PathBuf::from(get_home()).join(format!("Oceania/exports/swatches/tetra.{}", self.extension()))
+    }
+    pub fn encode(&self, palette: &ThemePalette) -> Vec<u8> {
+        match self {
+            SwatchFormat::Gpl => encode_gpl(palette).into_bytes(),
+            SwatchFormat::Ase => encode_ase(palette),
+            SwatchFormat::Aco => encode_aco(palette),
+            SwatchFormat::Kpl => encode_kpl(palette),
+        }
+    }
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<Swatch>, String> {
+        match self {
+            SwatchFormat::Gpl => decode_gpl(&String::from_utf8_lossy(bytes)),
+            SwatchFormat::Ase => decode_ase(bytes),
+            SwatchFormat::Aco => decode_aco(bytes),
+            SwatchFormat::Kpl => decode_kpl(bytes),
+        }
+    }
+}
+
+impl fmt::Display for SwatchFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            SwatchFormat::Gpl => gettext("GIMP/Inkscape palette (.gpl)"),
+            SwatchFormat::Ase => gettext("Adobe swatch exchange (.ase)"),
+            SwatchFormat::Aco => gettext("Adobe color swatches (.aco)"),
+            SwatchFormat::Kpl => gettext("Krita palette (.kpl)"),
+        };
+        write!(f, "{label}")
+    }
+}
+
+pub fn export(format: SwatchFormat, palette: &ThemePalette, path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format.encode(palette))
+}
+
+/// reads a swatch file and lays it over `base`; swatches named after a slot go to that slot,
+/// and files without any slot names fill the slots in order
+pub fn import(path: &Path, base: &ThemePalette) -> Result<ThemePalette, String> {
+    let format = SwatchFormat::from_path(path).ok_or(gettext("Unknown swatch file type"))?;
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    let swatches = format.decode(&bytes)?;
+    if swatches.is_empty() {
+        return Err(gettext("The swatch file has no colors"));
+    }
+    Ok(apply(&swatches, base))
+}
+
+fn slot_for_name(name: &str) -> Option<ColorSlot> {
+    let name = name.trim();
+    ColorSlot::ALL.into_iter().find(|slot| slot.key().eq_ignore_ascii_case(name) || slot.label() == name)
+}
+
+pub fn apply(swatches: &[Swatch], base: &ThemePalette) -> ThemePalette {
+    let mut palette = *base;
+    let named: Vec<(ColorSlot, Color)> = swatches.iter()
+        .filter_map(|swatch| Some((slot_for_name(swatch.name.as_deref()?)?, swatch.color)))
+        .collect();
+    if named.is_empty() {
+        for (slot, swatch) in ColorSlot::ALL.into_iter().zip(swatches) {
+            palette.set(slot, swatch.color);
+        }
+    } else {
+        for (slot, color) in named {
+            palette.set(slot, color);
+        }
+    }
+    palette
+}
+
+fn encode_gpl(palette: &ThemePalette) -> String {
+    let mut out = format!("GIMP Palette\nName: Tetra\nColumns: {}\n#\n", ColorSlot::ALL.len());
+    for slot in ColorSlot::ALL {
+        let [r, g, b] = rgb8(&palette.get(slot));
+        out.push_str(&format!("{r:3} {g:3} {b:3}\t{}\n", slot.key()));
+    }
+    out
+}
+
+fn decode_gpl(text: &str) -> Result<Vec<Swatch>, String> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(gettext("Not a GIMP palette"));
+    }
+    let mut out = vec![];
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Name:") || line.starts_with("Columns:") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let mut channel = || parts.next().and_then(|value| value.parse::<u8>().ok());
+        let (Some(r), Some(g), Some(b)) = (channel(), channel(), channel()) else {
+            return Err(format!("{} {line}", gettext("Malformed palette line:")));
+        };
+        let name: Vec<&str> = parts.collect();
+        out.push(Swatch {
+            name: if name.is_empty() { None } else { Some(name.join(" ")) },
+            color: Color::from_rgb8(r, g, b),
+        });
+    }
+    Ok(out)
+}
+
+fn utf16_be(name: &str) -> Vec<u8> {
+    name.encode_utf16().chain(std::iter::once(0)).flat_map(u16::to_be_bytes).collect()
+}
+
+/// reads big endian values out of a binary swatch file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or(gettext("The swatch file is truncated"))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+    /// `len` UTF-16 code units, the last of which may be a terminating null
+    fn utf16(&mut self, len: usize) -> Result<String, String> {
+        let units: Vec<u16> = self.take(len * 2)?.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+        Ok(String::from_utf16_lossy(&units).trim_end_matches('\0').to_string())
+    }
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+}
+
+fn encode_ase(palette: &ThemePalette) -> Vec<u8> {
+    let mut out = b"ASEF".to_vec();
+    out.extend(1u16.to_be_bytes());
+    out.extend(0u16.to_be_bytes());
+    out.extend((ColorSlot::ALL.len() as u32).to_be_bytes());
+    for slot in ColorSlot::ALL {
+        let color = palette.get(slot);
+        let name = utf16_be(slot.key());
+        let mut block = ((name.len() / 2) as u16).to_be_bytes().to_vec();
+        block.extend(name);
+        block.extend(b"RGB ");
+        for channel in [color.r, color.g, color.b] {
+            block.extend(channel.to_be_bytes());
+        }
+        // global color
+        block.extend(0u16.to_be_bytes());
+        out.extend(1u16.to_be_bytes());
+        out.extend((block.len() as u32).to_be_bytes());
+        out.extend(block);
+    }
+    out
+}
+
+fn decode_ase(bytes: &[u8]) -> Result<Vec<Swatch>, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.take(4)? != b"ASEF" {
+        return Err(gettext("Not an Adobe swatch exchange file"));
+    }
+    reader.take(4)?;
+    let count = reader.u32()?;
+    let mut out = vec![];
+    for _ in 0..count {
+        let kind = reader.u16()?;
+        let len = reader.u32()? as usize;
+        let mut block = Reader { bytes: reader.take(len)?, pos: 0 };
+        // group start and end blocks carry no colors
+        if kind != 1 {
+            continue;
+        }
+        let name_len = block.u16()? as usize;
+        let name = block.utf16(name_len)?;
+        let model = block.take(4)?;
+        let color = match model {
+            b"RGB " => Color::from_rgb(block.f32()?, block.f32()?, block.f32()?),
+            b"Gray" => {
+                let gray = block.f32()?;
+                Color::from_rgb(gray, gray, gray)
+            }
+            b"CMYK" => {
+                let (c, m, y, k) = (block.f32()?, block.f32()?, block.f32()?, block.f32()?);
+                Color::from_rgb((1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k))
+            }
+            // LAB swatches need a white point we don't know, skip them
+            _ => continue,
+        };
+        out.push(Swatch { name: Some(name), color });
+    }
+    Ok(out)
+}
+
+fn encode_aco(palette: &ThemePalette) -> Vec<u8> {
+    let channels = |color: &Color| {
+        let mut out = 0u16.to_be_bytes().to_vec();
+        for channel in [color.r, color.g, color.b] {
+            out.extend(((channel.clamp(0.0, 1.0) * 65535.0).round() as u16).to_be_bytes());
+        }
+        out.extend(0u16.to_be_bytes());
+        out
+    };
+    let count = (ColorSlot::ALL.len() as u16).to_be_bytes();
+    // version 1 holds only the colors, version 2 repeats them with names
+    let mut out = 1u16.to_be_bytes().to_vec();
+    out.extend(count);
+    for slot in ColorSlot::ALL {
+        out.extend(channels(&palette.get(slot)));
+    }
+    out.extend(2u16.to_be_bytes());
+    out.extend(count);
+    for slot in ColorSlot::ALL {
+        out.extend(channels(&palette.get(slot)));
+        let name = utf16_be(slot.key());
+        out.extend(((name.len() / 2) as u32).to_be_bytes());
+        out.extend(name);
+    }
+    out
+}
+
+fn decode_aco(bytes: &[u8]) -> Result<Vec<Swatch>, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut out = vec![];
+    while !reader.done() {
+        let version = reader.u16()?;
+        if version != 1 && version != 2 {
+            return Err(gettext("Not an Adobe color swatch file"));
+        }
+        let count = reader.u16()?;
+        let mut section = vec![];
+        for _ in 0..count {
+            let space = reader.u16()?;
+            let (w, x, y, z) = (reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?);
+            let name = if version == 2 {
+                let len = reader.u32()? as usize;
+                Some(reader.utf16(len)?)
+            } else {
+                None
+            };
+            let unit = |value: u16| value as f32 / 65535.0;
+            let color = match space {
+                0 => Color::from_rgb(unit(w), unit(x), unit(y)),
+                2 => {
+                    let (c, m, y, k) = (1.0 - unit(w), 1.0 - unit(x), 1.0 - unit(y), 1.0 - unit(z));
+                    Color::from_rgb((1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k))
+                }
+                8 => {
+                    let gray = 1.0 - w as f32 / 10000.0;
+                    Color::from_rgb(gray, gray, gray)
+                }
+                // HSB, Lab and the rest are rare enough to skip
+                _ => continue,
+            };
+            section.push(Swatch { name, color });
+        }
+        // a named version 2 section supersedes the version 1 colors
+        out = section;
+    }
+    Ok(out)
+}
+
+fn encode_kpl(palette: &ThemePalette) -> Vec<u8> {
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ColorSet version=\"1.0\" name=\"Tetra\" comment=\"\" columns=\"{}\" rows=\"1\" readonly=\"false\">\n", ColorSlot::ALL.len());
+    for (column, slot) in ColorSlot::ALL.into_iter().enumerate() {
+        let color = palette.get(slot);
+        xml.push_str(&format!(" <ColorSetEntry name=\"{0}\" id=\"{0}\" spot=\"false\" bitdepth=\"U8\">\n", slot.key()));
+        xml.push_str(&format!("  <RGB space=\"sRGB-elle-V2-srgbtrc.icc\" r=\"{}\" g=\"{}\" b=\"{}\"/>\n", color.r, color.g, color.b));
+        xml.push_str(&format!("  <Position row=\"0\" column=\"{column}\"/>\n </ColorSetEntry>\n"));
+    }
+    xml.push_str("</ColorSet>\n");
+
+    // krita sniffs the mimetype entry, which has to be first and uncompressed
+    zip_in_memory([
+        ("mimetype", CompressionMethod::Stored, b"krita/x-colorset".as_slice()),
+        ("colorset.xml", CompressionMethod::Deflated, xml.as_bytes()),
+        ("profiles.xml", CompressionMethod::Deflated, b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Profiles/>\n".as_slice()),
+    ])
+}
+
+/// a zip archive of `files`, in order and each compressed its own way
+pub fn zip_in_memory<'a>(files: impl IntoIterator<Item = (&'a str, CompressionMethod, &'a [u8])>) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let written: zip::result::ZipResult<Vec<u8>> = (|| {
+        for (path, method, contents) in files {
+            zip.start_file(path, FileOptions::default().compression_method(method))?;
+            zip.write_all(contents)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    })();
+    // everything is written to memory, so this can only fail on a zip bug
+    written.unwrap()
+}
+
+/// value of `name="..."` inside a single xml tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {name}=\""))? + name.len() + 3;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+fn decode_kpl(bytes: &[u8]) -> Result<Vec<Swatch>, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|error| error.to_string())?;
+    let mut xml = String::new();
+    archive.by_name("colorset.xml").map_err(|error| error.to_string())?
+        .read_to_string(&mut xml).map_err(|error| error.to_string())?;
+    let mut out = vec![];
+    for entry in xml.split("<ColorSetEntry").skip(1) {
+        let Some(open) = entry.split('>').next() else { continue };
+        let name = attribute(open, "name").map(unescape);
+        let Some(rgb) = entry.split("<RGB").nth(1).and_then(|rest| rest.split('>').next()) else {
+            continue;
+        };
+        let channel = |key: &str| attribute(rgb, key).and_then(|value| value.parse::<f32>().ok());
+        let (Some(r), Some(g), Some(b)) = (channel("r"), channel("g"), channel("b")) else {
+            return Err(gettext("Malformed Krita palette entry"));
+        };
+        out.push(Swatch { name, color: Color::from_rgb(r, g, b) });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::hex;
+
+    /// a palette with a different color in every slot
+    fn sample(seed: u8) -> ThemePalette {
+        let color = |i: u8| Color::from_rgb8(seed.wrapping_add(i * 23), i * 20, 255 - i * 11);
+        ThemePalette { bg1: color(0), bg2: color(1), bg3: color(2), txt: color(3), red: color(4), orange: color(5), yellow: color(6), green: color(7), blue: color(8), purple: color(9), pink: color(10) }
+    }
+
+    fn assert_same(a: &ThemePalette, b: &ThemePalette) {
+        for slot in ColorSlot::ALL {
+            assert_eq!(hex(&a.get(slot)), hex(&b.get(slot)), "{slot:?}");
+        }
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let palette = sample(0);
+        let base = sample(100);
+        for format in SwatchFormat::ALL {
+            let swatches = format.decode(&format.encode(&palette)).unwrap();
+            assert_eq!(swatches.len(), ColorSlot::ALL.len(), "{format:?}");
+            assert_same(&apply(&swatches, &base), &palette);
+        }
+    }
+
+    #[test]
+    fn malformed_gpl_is_rejected() {
+        assert!(decode_gpl("JASC-PAL\n").is_err());
+        assert!(decode_gpl("GIMP Palette\n12 x 3 red\n").is_err());
+        assert!(decode_gpl("GIMP Palette\n300 0 0 red\n").is_err());
+        assert_eq!(decode_gpl("GIMP Palette\nName: empty\n#\n").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn reader_stops_at_the_end() {
+        let mut reader = Reader { bytes: &[1, 2, 3], pos: 0 };
+        assert_eq!(reader.u16(), Ok(0x0102));
+        assert!(reader.u16().is_err());
+        assert!(reader.take(usize::MAX).is_err());
+        assert_eq!(reader.take(1), Ok(&[3][..]));
+        assert!(reader.done());
+        assert!(reader.utf16(1).is_err());
+    }
+
+    #[test]
+    fn truncated_binary_files_are_errors() {
+        let palette = sample(0);
+        let ase = SwatchFormat::Ase.encode(&palette);
+        for len in 0..ase.len() {
+            assert!(decode_ase(&ase[..len]).is_err(), "ase cut at {len}");
+        }
+        // an empty file has no colors, which `import` reports, and one cut right after the
+        // unnamed version 1 section is still a valid palette
+        let aco = SwatchFormat::Aco.encode(&palette);
+        let version_1 = 4 + ColorSlot::ALL.len() * 10;
+        for len in 1..aco.len() {
+            assert!(decode_aco(&aco[..len]).is_err() || len == version_1, "aco cut at {len}");
+        }
+        let kpl = SwatchFormat::Kpl.encode(&palette);
+        for len in (0..kpl.len()).step_by(7) {
+            let _ = decode_kpl(&kpl[..len]);
+        }
+    }
+
+    #[test]
+    fn malformed_binary_files_are_errors() {
+        // a count and block lengths far beyond the data
+        let mut ase = b"ASEF\0\x01\0\0".to_vec();
+        ase.extend(u32::MAX.to_be_bytes());
+        assert!(decode_ase(&ase).is_err());
+        ase.extend(1u16.to_be_bytes());
+        ase.extend(u32::MAX.to_be_bytes());
+        assert!(decode_ase(&ase).is_err());
+        // a name longer than its block
+        let mut ase = b"ASEF\0\x01\0\0".to_vec();
+        ase.extend(1u32.to_be_bytes());
+        ase.extend(1u16.to_be_bytes());
+        ase.extend(4u32.to_be_bytes());
+        ase.extend([0xff, 0xff, 0, 0]);
+        assert!(decode_ase(&ase).is_err());
+        assert!(decode_ase(b"RIFF....").is_err());
+
+        let mut aco = 2u16.to_be_bytes().to_vec();
+        aco.extend(1u16.to_be_bytes());
+        aco.extend([0; 10]);
+        aco.extend(u32::MAX.to_be_bytes());
+        assert!(decode_aco(&aco).is_err());
+        assert!(decode_aco(&[0, 7, 0, 0]).is_err());
+        assert!(decode_aco(&[0]).is_err());
+
+        assert!(decode_kpl(b"PK\x03\x04 not really a zip").is_err());
+        assert!(decode_kpl(&[]).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use gettextrs::gettext;
+use oceania_style::{string_to_color, ThemeFile};
+use serde_derive::{Serialize, Deserialize};
+use toml::{Table, Value};
+use crate::color::is_dark;
+use crate::library::{self, Library};
+use crate::palette::{ColorSlot, ThemePalette, Variant};
+
+/// the newest `schema_version` this build reads and writes
+pub const SCHEMA_VERSION: i64 = 2;
+
+/// the `ThemeFile` keys, in `ColorSlot::ALL` order
+pub const COLOR_KEYS: [&str; 11] = ["bg_color1", "bg_color2", "bg_color3", "txt_color", "red", "orange", "yellow", "green", "blue", "purple", "pink"];
+
+/// optional attribution and description, stored as a `[meta]` table that other Oceania apps ignore
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeMeta {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<Variant>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub license: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ThemeMeta {
+    pub fn is_empty(&self) -> bool {
+        *self == ThemeMeta::default()
+    }
+    /// the declared variant, falling back to guessing from the background
+    pub fn variant_of(&self, palette: &ThemePalette) -> Variant {
+        self.variant.unwrap_or(palette.variant())
+    }
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() { "Tetra" } else { &self.name }
+    }
+    pub fn tags_text(&self) -> String {
+        self.tags.join(", ")
+    }
+    pub fn set_tags(&mut self, text: &str) {
+        self.tags = text.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect();
+    }
+}
+
+/// the on-disk theme: the plain `ThemeFile` colors every Oceania app reads, plus Tetra's extras
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeDocument {
+    pub schema_version: i64,
+    /// a library theme to take missing colors from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    #[serde(flatten)]
+    pub colors: ThemeFile,
+    #[serde(default, skip_serializing_if = "ThemeMeta::is_empty")]
+    pub meta: ThemeMeta,
+}
+
+/// a derived theme on disk, holding only the colors that differ from its base
+#[derive(Serialize)]
+struct DerivedDocument<'a> {
+    schema_version: i64,
+    extends: &'a str,
+    #[serde(flatten)]
+    overrides: BTreeMap<&'static str, String>,
+    #[serde(skip_serializing_if = "ThemeMeta::is_empty")]
+    meta: &'a ThemeMeta,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub palette: ThemePalette,
+    pub meta: ThemeMeta,
+    pub extends: Option<String>,
+}
+
+impl Theme {
+    /// the resolved palette of the theme this one extends
+    pub fn base(&self, library: &Library) -> Option<ThemePalette> {
+        library.load(self.extends.as_ref()?).ok().map(|theme| theme.palette)
+    }
+}
+
+/// the live theme every Oceania app reads
+pub fn theme_path() -> String {
+    Library::home().theme_path()
+}
+
+/// `MIGRATIONS[n]` upgrades a table from schema n + 1 to n + 2
+const MIGRATIONS: [fn(&mut Table); 1] = [v1_to_v2];
+
+/// version 1 files predate `schema_version` and `[meta]`. they may hold unpadded hex
+/// like `"a5f"`, which readers parse as a number, so padding on the left keeps the color.
+fn v1_to_v2(table: &mut Table) {
+    for key in COLOR_KEYS {
+        if let Some(Value::String(value)) = table.get_mut(key) {
+            let digits = value.trim().trim_start_matches('#').to_lowercase();
+            *value = format!("{digits:0>6}");
+        }
+    }
+    let Some(Value::String(bg1)) = table.get("bg_color1") else {
+        return;
+    };
+    let variant = if is_dark(&string_to_color(bg1.clone())) { Variant::Dark } else { Variant::Light };
+    let meta = table.entry("meta").or_insert(Value::Table(Table::new()));
+    if let Value::Table(meta) = meta {
+        meta.entry("variant").or_insert(Value::String(variant.key().to_string()));
+    }
+}
+
+/// brings a parsed theme up to `SCHEMA_VERSION`. newer files are left alone and read best-effort.
+pub fn migrate(table: &mut Table) {
+    let version = table.get("schema_version").and_then(Value::as_integer).unwrap_or(1).max(1);
+    for migration in MIGRATIONS.iter().skip(version as usize - 1) {
+        migration(table);
+    }
+    if version < SCHEMA_VERSION {
+        table.insert("schema_version".to_string(), Value::Integer(SCHEMA_VERSION));
+    }
+}
+
+/// fills in the colors a derived theme leaves out, following `extends` through `library`.
+/// `chain` collects the bases visited so far, so a theme that ends up extending itself is an error.
+fn resolve(table: &mut Table, chain: &mut Vec<String>, library: &Library) -> Result<(), String> {
+    migrate(table);
+    let Some(extends) = table.get("extends").and_then(Value::as_str).map(str::to_string) else {
+        return Ok(());
+    };
+    // `theme.toml` and other fully written themes keep reading after their base is renamed or deleted
+    if COLOR_KEYS.iter().all(|key| table.contains_key(*key)) {
+        return Ok(());
+    }
+    // the name becomes a path, so it gets the same checks as one typed into the library
+    library::validate(&extends)?;
+    let looped = chain.contains(&extends);
+    chain.push(extends.clone());
+    if looped {
+        return Err(format!("{} {}", gettext("Theme inheritance loops:"), chain.join(" → ")));
+    }
+    let value = std::fs::read_to_string(library.path(&extends)).map_err(|_| format!("{} {extends}", gettext("Missing base theme:")))?;
+    let mut base: Table = toml::from_str(&value).map_err(|error| format!("{extends}: {error}"))?;
+    resolve(&mut base, chain, library)?;
+    for key in COLOR_KEYS {
+        if let (false, Some(color)) = (table.contains_key(key), base.get(key)) {
+            table.insert(key.to_string(), color.clone());
+        }
+    }
+    Ok(())
+}
+
+pub fn parse(value: &str, library: &Library) -> Result<ThemeDocument, String> {
+    let mut table: Table = toml::from_str(value).map_err(|error| error.to_string())?;
+    resolve(&mut table, &mut vec![], library)?;
+    Value::Table(table).try_into().map_err(|error: toml::de::Error| error.to_string())
+}
+
+pub fn load(path: &str, library: &Library) -> Result<Theme, String> {
+    let value = std::fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let document = parse(&value, library)?;
+    Ok(Theme { palette: ThemePalette::from_file(&document.colors), meta: document.meta, extends: document.extends })
+}
+
+/// why replacing the file at `path` would lose something: it doesn't parse, or a newer Tetra wrote it
+/// and this one would drop what it doesn't know. `None` when it's safe, missing files included.
+pub fn overwrite_risk(path: &str, library: &Library) -> Option<String> {
+    let value = std::fs::read_to_string(path).ok()?;
+    if let Err(error) = parse(&value, library) {
+        return Some(error);
+    }
+    let version = toml::from_str::<Table>(&value).ok()?.get("schema_version").and_then(Value::as_integer)?;
+    (version > SCHEMA_VERSION).then(|| format!("{} {version}", gettext("It was written for a newer schema version:")))
+}
+
+/// `overwrite_risk` as an error, for writers with nobody to ask
+pub fn refuse_risky_overwrite(path: &str, library: &Library) -> Result<(), String> {
+    match overwrite_risk(path, library) {
+        Some(risk) => Err(format!("{} {risk}", gettext("Leaving theme.toml as it is, since this version can't keep it:"))),
+        None => Ok(()),
+    }
+}
+
+pub fn read(path: &str, library: &Library) -> Option<(ThemePalette, ThemeMeta)> {
+    load(path, library).ok().map(|theme| (theme.palette, theme.meta))
+}
+
+/// every color written out, so apps that don't know about `extends` can still read it
+pub fn to_string(theme: &Theme) -> String {
+    to_string_as(theme, SCHEMA_VERSION)
+}
+
+/// writes the layout of an older schema, dropping whatever it can't hold
+pub fn to_string_as(theme: &Theme, schema_version: i64) -> String {
+    if schema_version < 2 {
+        return toml::to_string(&theme.palette.to_file()).unwrap();
+    }
+    let document = ThemeDocument { schema_version, extends: theme.extends.clone(), colors: theme.palette.to_file(), meta: theme.meta.clone() };
+    toml::to_string(&document).unwrap()
+}
+
+/// only the colors that differ from `base`, for themes kept in the library
+pub fn to_string_derived(theme: &Theme, extends: &str, base: &ThemePalette) -> String {
+    let overrides = ColorSlot::ALL.into_iter().zip(COLOR_KEYS)
+        .filter(|(slot, _)| theme.palette.get(*slot) != base.get(*slot))
+        .map(|(slot, key)| (key, crate::string_from_col(&theme.palette.get(slot))))
+        .collect();
+    toml::to_string(&DerivedDocument { schema_version: SCHEMA_VERSION, extends, overrides, meta: &theme.meta }).unwrap()
+}
+
+pub fn write(path: &str, theme: &Theme) -> std::io::Result<()> {
+    std::fs::write(path, to_string(theme))
+}
+
+pub fn write_as(path: &str, theme: &Theme, schema_version: i64) -> std::io::Result<()> {
+    std::fs::write(path, to_string_as(theme, schema_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::tests::scratch;
+
+    fn sample() -> Theme {
+        let meta = ThemeMeta { name: String::from("Sample"), author: String::from("someone"), ..ThemeMeta::default() };
+        Theme { meta, ..library::tests::sample() }
+    }
+
+    #[test]
+    fn v1_files_gain_a_variant() {
+        let library = scratch("v1");
+        let document = parse("bg_color1 = \"1e1e2e\"\nbg_color2 = \"a5f\"\nbg_color3 = \"#313244\"\ntxt_color = \"cdd6f4\"\nred = \"f38ba8\"\norange = \"fab387\"\nyellow = \"f9e2af\"\ngreen = \"a6e3a1\"\nblue = \"89b4fa\"\npurple = \"cba6f7\"\npink = \"f5c2e7\"\n", &library).unwrap();
+        assert_eq!(document.schema_version, SCHEMA_VERSION);
+        assert_eq!(document.meta.variant, Some(Variant::Dark));
+        assert_eq!(document.colors.bg_color2, "000a5f");
+        assert_eq!(document.colors.bg_color3, "313244");
+    }
+
+    #[test]
+    fn v1_output_reads_as_a_plain_theme_file() {
+        let theme = sample();
+        let written = to_string_as(&theme, 1);
+        assert!(!written.contains("schema_version") && !written.contains("[meta]"));
+        let file: ThemeFile = toml::from_str(&written).unwrap();
+        assert_eq!(ThemePalette::from_file(&file), theme.palette);
+    }
+
+    #[test]
+    fn current_output_round_trips() {
+        let theme = sample();
+        let document = parse(&to_string(&theme), &scratch("round trip")).unwrap();
+        assert_eq!(ThemePalette::from_file(&document.colors), theme.palette);
+        assert_eq!(document.meta, theme.meta);
+    }
+
+    #[test]
+    fn newer_files_are_read_but_not_overwritten() {
+        let newer = to_string(&sample()).replace(&format!("schema_version = {SCHEMA_VERSION}"), "schema_version = 99") + "\n[future]\nkey = 1\n";
+        let mut table: Table = toml::from_str(&newer).unwrap();
+        migrate(&mut table);
+        assert_eq!(table.get("schema_version").and_then(Value::as_integer), Some(99));
+        assert!(table.contains_key("future"));
+        let library = scratch("newer");
+        assert_eq!(ThemePalette::from_file(&parse(&newer, &library).unwrap().colors), sample().palette);
+
+        let path = library.theme_path();
+        std::fs::write(&path, &newer).unwrap();
+        assert!(overwrite_risk(&path, &library).is_some());
+        std::fs::write(&path, "bg_color1 = ").unwrap();
+        assert!(overwrite_risk(&path, &library).is_some());
+        std::fs::write(&path, to_string(&sample())).unwrap();
+        assert_eq!(overwrite_risk(&path, &library), None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(overwrite_risk(&path, &library), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use iced::Color;
+use gettextrs::gettext;
+use oceania_style::get_home;
+use serde_json::{json, Map, Value};
+use crate::color::{hex, lighten, mix};
+use crate::palette::ThemePalette;
+
+/// where pywal keeps its generated colors, and so where scripts look for them
+pub fn pywal_dir() -> PathBuf {
+    match std::env::var("XDG_CACHE_HOME") {
+        Ok(value) if !value.is_empty() => PathBuf::from(value).join("wal"),
+        _ => PathBuf::from(get_home()).join(".cache/wal"),
+    }
+}
+
+/// `*.color` resources to `#include` from `~/.Xresources`, or load with `xrdb -merge`
+pub fn xresources(palette: &ThemePalette) -> String {
+    let mut out = String::from("! Generated by Tetra, edits will be overwritten\n");
+    out.push_str(&format!("*.background: {}\n", hex(&palette.bg1)));
+    out.push_str(&format!("*.foreground: {}\n", hex(&palette.txt)));
+    out.push_str(&format!("*.cursorColor: {}\n", hex(&palette.txt)));
+    for (i, color) in palette.ansi().iter().enumerate() {
+        out.push_str(&format!("*.color{i}: {}\n", hex(color)));
+    }
+    // base16 style extras, which Tetra reads back as orange and pink
+    out.push_str(&format!("*.color16: {}\n", hex(&palette.orange)));
+    out.push_str(&format!("*.color17: {}\n", hex(&palette.pink)));
+    out
+}
+
+pub fn pywal_json(palette: &ThemePalette) -> String {
+    let mut colors = Map::new();
+    for (i, color) in palette.ansi().iter().enumerate() {
+        colors.insert(format!("color{i}"), hex(color).into());
+    }
+    let wal = json!({
+        "wallpaper": "None",
+        "alpha": "100",
+        "special": {
+            "background": hex(&palette.bg1),
+            "foreground": hex(&palette.txt),
+            "cursor": hex(&palette.txt),
+        },
+        "colors": colors,
+    });
+    serde_json::to_string_pretty(&wal).unwrap()
+}
+
+/// the shell flavour of the same colors, sourced by many pywal scripts
+pub fn pywal_sh(palette: &ThemePalette) -> String {
+    let mut out = String::from("# Generated by Tetra, edits will be overwritten\nwallpaper='None'\n\n");
+    out.push_str(&format!("background='{}'\n", hex(&palette.bg1)));
+    out.push_str(&format!("foreground='{}'\n", hex(&palette.txt)));
+    out.push_str(&format!("cursor='{}'\n\n", hex(&palette.txt)));
+    let ansi = palette.ansi();
+    for (i, color) in ansi.iter().enumerate() {
+        out.push_str(&format!("color{i}='{}'\n", hex(color)));
+    }
+    out.push('\n');
+    let names: Vec<String> = (0..ansi.len()).map(|i| format!("color{i}")).collect();
+    out.push_str(&format!("export {} background foreground cursor wallpaper\n", names.join(" ")));
+    out
+}
+
+/// the colors a sixteen color scheme provides, by X resource name
+struct AnsiColors(HashMap<String, Color>);
+
+impl AnsiColors {
+    fn get(&self, key: &str) -> Option<Color> {
+        self.0.get(key).copied()
+    }
+    /// maps terminal colors onto the Tetra slots, deriving what the scheme doesn't have
+    fn to_palette(&self, base: &ThemePalette) -> Result<ThemePalette, String> {
+        let (Some(bg1), Some(txt)) = (self.get("background").or(self.get("color0")), self.get("foreground").or(self.get("color7"))) else {
+            return Err(gettext("No background or foreground color found"));
+        };
+        let dim = self.get("color8").unwrap_or(mix(bg1, txt, 0.3));
+        let mut palette = *base;
+        palette.bg1 = bg1;
+        palette.bg2 = mix(bg1, dim, 0.08);
+        palette.bg3 = mix(bg1, dim, 0.16);
+        palette.txt = txt;
+        palette.red = self.get("color1").unwrap_or(base.red);
+        palette.green = self.get("color2").unwrap_or(base.green);
+        palette.yellow = self.get("color3").unwrap_or(base.yellow);
+        palette.blue = self.get("color4").unwrap_or(base.blue);
+        palette.purple = self.get("color5").unwrap_or(base.purple);
+        palette.orange = self.get("color16").unwrap_or(mix(palette.red, palette.yellow, 0.5));
+        palette.pink = match (self.get("color17"), self.get("color13")) {
+            (Some(pink), _) => pink,
+            (None, Some(bright)) if bright != palette.purple => bright,
+            _ => lighten(mix(palette.purple, palette.red, 0.4), 0.2),
+        };
+        Ok(palette)
+    }
+}
+
+fn parse_hex(value: &str) -> Option<Color> {
+    let value = value.trim().trim_start_matches('#');
+    let value = value.strip_prefix("rgb:").map(|rgb| rgb.replace('/', "")).unwrap_or(value.to_string());
+    // `#rgb` shorthand, each digit doubled as in CSS
+    let value = match value.len() {
+        3 => value.chars().flat_map(|digit| [digit, digit]).collect(),
+        6 => value,
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&value[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn parse_xresources(text: &str) -> AnsiColors {
+    let mut defines: HashMap<String, String> = HashMap::new();
+    let mut colors = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(define) = line.strip_prefix("#define") {
+            let mut parts = define.split_whitespace();
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                defines.insert(name.to_string(), value.to_string());
+            }
+            continue;
+        }
+        if line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else { continue };
+        // `*.color1`, `*color1`, `URxvt.color1` and friends all name the same resource
+        let name = key.trim().rsplit(['.', '*']).next().unwrap_or_default();
+        let name = match name {
+            "colorBG" => "background",
+            "colorFG" => "foreground",
+            other => other,
+        };
+        let value = value.trim();
+        let value = defines.get(value).map(String::as_str).unwrap_or(value);
+        if let Some(color) = parse_hex(value) {
+            // whichever definition comes first in the file wins, later ones for the same color are ignored
+            colors.entry(name.to_string()).or_insert(color);
+        }
+    }
+    AnsiColors(colors)
+}
+
+fn parse_pywal(text: &str) -> Result<AnsiColors, String> {
+    let value: Value = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    let mut colors = HashMap::new();
+    for section in ["special", "colors"] {
+        if let Some(Value::Object(map)) = value.get(section) {
+            for (key, value) in map {
+                if let Some(color) = value.as_str().and_then(parse_hex) {
+                    colors.insert(key.clone(), color);
+                }
+            }
+        }
+    }
+    Ok(AnsiColors(colors))
+}
+
+/// pywal's `colors.json` when pywal has run, otherwise `~/.Xresources`
+pub fn default_import_path() -> PathBuf {
+    let wal = pywal_dir().join("colors.json");
+    if wal.exists() { wal } else { PathBuf::from(get_home()).join(".Xresources") }
+}
+
+pub fn import(path: &Path, base: &ThemePalette) -> Result<ThemePalette, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let colors = if path.extension().is_some_and(|extension| extension == "json") {
+        parse_pywal(&text)?
+    } else {
+        parse_xresources(&text)
+    };
+    colors.to_palette(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shorthand_hex_is_read() {
+        let colors = parse_xresources("*.color1: #f80\n*.color2: rgb:00/80/ff\n*.color1: #000000\n");
+        assert_eq!(colors.0.get("color1"), Some(&Color::from_rgb8(0xff, 0x88, 0x00)));
+        assert_eq!(colors.0.get("color2"), Some(&Color::from_rgb8(0x00, 0x80, 0xff)));
+    }
+}
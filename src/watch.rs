@@ -0,0 +1,36 @@
+use std::path::{Path, PathBuf};
+use iced::futures::channel::mpsc::Sender;
+use iced::futures::SinkExt;
+use iced::Subscription;
+use inotify::{Inotify, WatchMask};
+
+/// fires whenever something finishes writing `path`. the directory is watched rather than the file,
+/// so tools that replace the file by renaming over it are noticed too.
+pub fn changes(path: PathBuf) -> Subscription<()> {
+    struct Watch;
+    iced::subscription::channel(std::any::TypeId::of::<Watch>(), 16, move |output| async move {
+        // inotify only offers a blocking read without tokio, so it gets a thread of its own
+        std::thread::spawn(move || {
+            let _ = watch(&path, output);
+        });
+        loop {
+            std::future::pending::<()>().await;
+        }
+    })
+}
+
+fn watch(path: &Path, mut output: Sender<()>) -> std::io::Result<()> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    let mut inotify = Inotify::init()?;
+    inotify.watches().add(dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)?;
+    let mut buffer = [0; 4096];
+    loop {
+        let events = inotify.read_events_blocking(&mut buffer)?;
+        if events.into_iter().any(|event| event.name == Some(name)) && iced::futures::executor::block_on(output.send(())).is_err() {
+            // the subscription was dropped
+            return Ok(());
+        }
+    }
+}